@@ -0,0 +1,176 @@
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::client::NjallaClient;
+use crate::domain::Domain;
+use crate::error::{Error, Result};
+use crate::record::{Record, RecordsResponse};
+
+/// Source of batch IDs, so calls cannot be taken from another batch's results.
+static NEXT_BATCH_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Calls queued to be sent as one JSON-RPC batch. Start with
+/// `NjallaClient::batch()`.
+///
+/// The whole batch counts as one request for retries and rate limiting. It
+/// is retried only if every call in it is read-only.
+#[derive(Debug)]
+pub struct Batch<'a> {
+    client: &'a NjallaClient,
+    id: u64,
+    calls: Vec<(String, Value)>,
+}
+
+/// A queued call, used to take its result from `BatchResults`.
+pub struct BatchCall<T> {
+    batch: u64,
+    index: usize,
+    parse: fn(Value) -> Result<T>,
+}
+
+// Manual impls so `T` does not need to be `Clone`, `Copy` or `Debug`.
+impl<T> Clone for BatchCall<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for BatchCall<T> {}
+
+impl<T> fmt::Debug for BatchCall<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchCall")
+            .field("batch", &self.batch)
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Results of a sent batch, one per queued call.
+#[derive(Debug)]
+pub struct BatchResults {
+    batch: u64,
+    results: Vec<Option<Result<Value>>>,
+}
+
+impl Batch<'_> {
+    /// Queues a raw API call whose result deserializes into `T`.
+    pub fn call<T: DeserializeOwned>(&mut self, method: &str, params: Value) -> BatchCall<T> {
+        self.push(method, params, |v| Ok(serde_json::from_value(v)?))
+    }
+
+    /// Queues `get_domain`.
+    pub fn get_domain(&mut self, domain: &str) -> BatchCall<Domain> {
+        self.call("get-domain", json!({ "domain": domain }))
+    }
+
+    /// Queues `list_records`.
+    pub fn list_records(&mut self, domain: &str) -> BatchCall<Vec<Record>> {
+        self.push("list-records", json!({ "domain": domain }), |v| {
+            Ok(serde_json::from_value::<RecordsResponse>(v)?.records)
+        })
+    }
+
+    /// Queues `edit_record`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Json` if the record cannot be serialized.
+    pub fn edit_record(&mut self, domain: &str, record: &Record) -> Result<BatchCall<()>> {
+        let mut params = serde_json::to_value(record)?;
+        params["domain"] = json!(domain);
+        Ok(self.push("edit-record", params, |_| Ok(())))
+    }
+
+    /// Queues `remove_record`.
+    pub fn remove_record(&mut self, domain: &str, id: &str) -> BatchCall<()> {
+        self.push(
+            "remove-record",
+            json!({ "domain": domain, "id": id }),
+            |_| Ok(()),
+        )
+    }
+
+    fn push<T>(
+        &mut self,
+        method: &str,
+        params: Value,
+        parse: fn(Value) -> Result<T>,
+    ) -> BatchCall<T> {
+        self.calls.push((method.to_owned(), params));
+        BatchCall {
+            batch: self.id,
+            index: self.calls.len() - 1,
+            parse,
+        }
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Sends all queued calls in one HTTP request. If the API rejects the
+    /// batch, the calls are sent one at a time instead. Calls that change
+    /// state are only resent if the API made clear it ran none of them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request as a whole fails, e.g. on network
+    /// failure or an unexpected reply. Errors of single calls are returned
+    /// by `BatchResults::take`.
+    pub async fn send(self) -> Result<BatchResults> {
+        if self.calls.is_empty() {
+            return Ok(BatchResults {
+                batch: self.id,
+                results: Vec::new(),
+            });
+        }
+        let results = self.client.call_batch(&self.calls).await?;
+        Ok(BatchResults {
+            batch: self.id,
+            results: results.into_iter().map(Some).collect(),
+        })
+    }
+}
+
+impl BatchResults {
+    /// Takes the result of `call`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the call's own error, e.g. `Error::Api`, or `Error::Invalid`
+    /// if the result was already taken or `call` is from another batch.
+    pub fn take<T>(&mut self, call: BatchCall<T>) -> Result<T> {
+        if call.batch != self.batch {
+            return Err(Error::Invalid("call is from another batch".into()));
+        }
+        let result = self
+            .results
+            .get_mut(call.index)
+            .and_then(Option::take)
+            .ok_or_else(|| {
+                Error::Invalid(format!("result of batch call {} already taken", call.index))
+            })?;
+        (call.parse)(result?)
+    }
+}
+
+impl NjallaClient {
+    /// Starts a JSON-RPC batch.
+    #[must_use]
+    pub fn batch(&self) -> Batch<'_> {
+        Batch {
+            client: self,
+            id: NEXT_BATCH_ID.fetch_add(1, Ordering::Relaxed),
+            calls: Vec::new(),
+        }
+    }
+}
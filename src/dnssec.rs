@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::client::NjallaClient;
+use crate::error::Result;
+
+/// A DNSSEC entry as stored on a domain. DS entries carry `key_tag`,
+/// `digest_type` and `digest`; DNSKEY entries carry `flags` and `public_key`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Dnssec {
+    pub id: String,
+    pub algorithm: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_tag: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest_type: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+}
+
+/// A delegation signer record to publish at the parent zone.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DsRecord {
+    pub key_tag: u16,
+    pub algorithm: u8,
+    pub digest_type: u8,
+    pub digest: String,
+}
+
+/// A public DNSKEY; the registry derives the DS record from it.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DnsKey {
+    pub flags: u16,
+    pub algorithm: u8,
+    pub public_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DnssecResponse {
+    dnssec: Vec<Dnssec>,
+}
+
+impl NjallaClient {
+    /// Lists all DNSSEC entries for a domain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or API rejection.
+    pub async fn list_dnssec(&self, domain: &str) -> Result<Vec<Dnssec>> {
+        let resp: DnssecResponse = self
+            .call("list-dnssec", json!({ "domain": domain }))
+            .await?;
+        Ok(resp.dnssec)
+    }
+
+    /// Adds a DS record to a domain. Returns the created entry with its ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the record is invalid.
+    pub async fn add_ds(&self, domain: &str, ds: &DsRecord) -> Result<Dnssec> {
+        let mut params = serde_json::to_value(ds)?;
+        params["domain"] = json!(domain);
+        self.call("add-dnssec", params).await
+    }
+
+    /// Adds a DNSKEY to a domain. Returns the created entry with its ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the key is invalid.
+    pub async fn add_dnskey(&self, domain: &str, key: &DnsKey) -> Result<Dnssec> {
+        let mut params = serde_json::to_value(key)?;
+        params["domain"] = json!(domain);
+        self.call("add-dnssec", params).await
+    }
+
+    /// Removes a DNSSEC entry by ID from a domain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the entry is not found.
+    pub async fn remove_dnssec(&self, domain: &str, id: &str) -> Result<()> {
+        self.call_void("remove-dnssec", json!({ "domain": domain, "id": id }))
+            .await
+    }
+}
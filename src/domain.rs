@@ -1,20 +1,43 @@
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::BTreeMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
 
 use crate::client::NjallaClient;
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::expiry::Expiry;
+use crate::status::{DomainStatus, TaskStatus};
+use crate::task::TaskHandle;
+use crate::wallet::Amount;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Domain {
     pub name: String,
-    pub status: String,
-    pub expiry: String,
+    pub status: DomainStatus,
+    pub expiry: Expiry,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub locked: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mailforwarding: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_nameservers: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nameservers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autorenew: Option<bool>,
+}
+
+/// Patch for `edit_domain`. Only fields that are `Some` are sent.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DomainUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nameservers: Option<Vec<String>>,
+    #[serde(rename = "lock", skip_serializing_if = "Option::is_none")]
+    pub locked: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mailforwarding: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autorenew: Option<bool>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -24,6 +47,30 @@ pub struct MarketDomain {
     pub price: i64,
 }
 
+/// A top-level domain Njalla can register, with yearly prices.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Tld {
+    #[serde(default)]
+    pub name: String,
+    pub price: Amount,
+    #[serde(alias = "renew_price")]
+    pub renewal_price: Amount,
+    #[serde(default)]
+    pub dnssec: bool,
+    #[serde(alias = "max_year")]
+    pub max_years: u32,
+}
+
+/// A glue record (host object) for a nameserver under the domain.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Glue {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address4: Option<Ipv4Addr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address6: Option<Ipv6Addr>,
+}
+
 #[derive(Debug, Deserialize)]
 struct DomainsResponse {
     domains: Vec<Domain>,
@@ -35,13 +82,28 @@ struct MarketDomainsResponse {
 }
 
 #[derive(Debug, Deserialize)]
-struct TaskResponse {
-    task: String,
+struct TldsResponse {
+    tlds: BTreeMap<String, Tld>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthCodeResponse {
+    auth_code: String,
 }
 
 #[derive(Debug, Deserialize)]
-struct TaskStatus {
-    status: String,
+struct GlueResponse {
+    glue: Vec<Glue>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct TaskResponse {
+    pub(crate) task: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TaskStatusResponse {
+    status: TaskStatus,
 }
 
 impl NjallaClient {
@@ -64,6 +126,74 @@ impl NjallaClient {
         self.call("get-domain", json!({ "domain": domain })).await
     }
 
+    /// Edits domain settings. Only the fields set in `update` are changed.
+    ///
+    /// If `update.nameservers` is set, the domain is fetched first so the list
+    /// can be checked against `max_nameservers`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Invalid` if too many nameservers are given, or an error
+    /// on network failure or API rejection.
+    pub async fn edit_domain(&self, domain: &str, update: &DomainUpdate) -> Result<()> {
+        if let Some(nameservers) = &update.nameservers {
+            let current = self.get_domain(domain).await?;
+            if let Some(max) = current.max_nameservers
+                && usize::try_from(max).is_ok_and(|max| nameservers.len() > max)
+            {
+                return Err(Error::Invalid(format!(
+                    "{domain} allows at most {max} nameservers, got {}",
+                    nameservers.len()
+                )));
+            }
+        }
+        let mut params = serde_json::to_value(update)?;
+        params["domain"] = json!(domain);
+        self.call_void("edit-domain", params).await
+    }
+
+    /// Lists all glue records for a domain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or API rejection.
+    pub async fn list_glue(&self, domain: &str) -> Result<Vec<Glue>> {
+        let resp: GlueResponse = self.call("list-glue", json!({ "domain": domain })).await?;
+        Ok(resp.glue)
+    }
+
+    /// Adds a glue record to a domain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the glue record is invalid.
+    pub async fn add_glue(&self, domain: &str, glue: &Glue) -> Result<()> {
+        let mut params = serde_json::to_value(glue)?;
+        params["domain"] = json!(domain);
+        self.call_void("add-glue", params).await
+    }
+
+    /// Edits an existing glue record. Both addresses are sent; fetch first if patching.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the glue record is not found.
+    pub async fn edit_glue(&self, domain: &str, glue: &Glue) -> Result<()> {
+        let mut params = serde_json::to_value(glue)?;
+        params["domain"] = json!(domain);
+        self.call_void("edit-glue", params).await
+    }
+
+    /// Removes a glue record by name from a domain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the glue record is not found.
+    pub async fn remove_glue(&self, domain: &str, name: &str) -> Result<()> {
+        self.call_void("remove-glue", json!({ "domain": domain, "name": name }))
+            .await
+    }
+
     /// Searches for available domains matching a query.
     ///
     /// # Errors
@@ -75,28 +205,102 @@ impl NjallaClient {
         Ok(resp.domains)
     }
 
+    /// Lists all TLDs available for registration, sorted by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or API rejection.
+    pub async fn list_tlds(&self) -> Result<Vec<Tld>> {
+        let resp: TldsResponse = self.call("get-tlds", json!({})).await?;
+        Ok(resp
+            .tlds
+            .into_iter()
+            .map(|(name, tld)| Tld { name, ..tld })
+            .collect())
+    }
+
     /// Checks the status of an async task (e.g. domain registration).
     ///
     /// # Errors
     ///
     /// Returns an error on network failure or API rejection.
-    pub async fn check_task(&self, id: &str) -> Result<String> {
-        let resp: TaskStatus = self.call("check-task", json!({ "id": id })).await?;
+    pub async fn check_task(&self, id: &str) -> Result<TaskStatus> {
+        let resp: TaskStatusResponse = self.call("check-task", json!({ "id": id })).await?;
         Ok(resp.status)
     }
 
-    /// Registers a domain for a given number of years. Returns a task ID.
+    /// Registers a domain for a given number of years. Returns a handle to the task.
     ///
     /// # Errors
     ///
     /// Returns an error on network failure or if registration is rejected.
-    pub async fn register_domain(&self, domain: &str, years: u32) -> Result<String> {
+    pub async fn register_domain(&self, domain: &str, years: u32) -> Result<TaskHandle> {
         let resp: TaskResponse = self
             .call(
                 "register-domain",
                 json!({ "domain": domain, "years": years }),
             )
             .await?;
-        Ok(resp.task)
+        Ok(self.task(resp.task))
+    }
+
+    /// Transfers a domain in from another registrar. Returns a handle to the task.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the transfer is rejected.
+    pub async fn import_domain(&self, domain: &str, auth_code: &str) -> Result<TaskHandle> {
+        let resp: TaskResponse = self
+            .call(
+                "import-domain",
+                json!({ "domain": domain, "auth_code": auth_code }),
+            )
+            .await?;
+        Ok(self.task(resp.task))
+    }
+
+    /// Gets the auth code for transferring a domain out, unlocking the
+    /// domain first if it is locked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or API rejection.
+    pub async fn get_auth_code(&self, domain: &str) -> Result<String> {
+        if self.get_domain(domain).await?.locked == Some(true) {
+            let unlock = DomainUpdate {
+                locked: Some(false),
+                ..DomainUpdate::default()
+            };
+            self.edit_domain(domain, &unlock).await?;
+        }
+        let resp: AuthCodeResponse = self
+            .call("get-auth-code", json!({ "domain": domain }))
+            .await?;
+        Ok(resp.auth_code)
+    }
+
+    /// Renews a domain for a given number of years. Returns a handle to the task.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if renewal is rejected.
+    pub async fn renew_domain(&self, domain: &str, years: u32) -> Result<TaskHandle> {
+        let resp: TaskResponse = self
+            .call("renew-domain", json!({ "domain": domain, "years": years }))
+            .await?;
+        Ok(self.task(resp.task))
+    }
+
+    /// Turns automatic renewal on or off for a domain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or API rejection.
+    pub async fn set_domain_autorenew(&self, domain: &str, autorenew: bool) -> Result<()> {
+        let update = DomainUpdate {
+            autorenew: Some(autorenew),
+            ..DomainUpdate::default()
+        };
+        self.edit_domain(domain, &update).await
     }
 }
@@ -1,4 +1,7 @@
 use serde::Deserialize;
+use std::fmt;
+
+use crate::status::TaskStatus;
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct JsonRpcError {
@@ -20,11 +23,47 @@ pub enum Error {
     #[error("missing 'result' in response")]
     MissingResult,
 
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+
     #[error("env: {0}")]
     Env(#[from] std::env::VarError),
 
     #[error("not found: {0}")]
     NotFound(String),
+
+    #[error("invalid: {0}")]
+    Invalid(String),
+
+    #[error("task {id} failed: {status}")]
+    TaskFailed { id: String, status: TaskStatus },
+
+    #[error("timed out: {0}")]
+    Timeout(String),
+
+    #[error("validation failed: {}", join_fields(.0))]
+    Validation(Vec<FieldError>),
+}
+
+/// A single problem found by client-side validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+fn join_fields(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 impl From<JsonRpcError> for Error {
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc};
+
+/// An expiry date exactly as the API returned it.
+///
+/// With the `chrono` feature (on by default) it can be parsed into a UTC
+/// timestamp; the raw string is kept so unexpected formats never fail
+/// deserialization.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Expiry(String);
+
+impl Expiry {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Expiry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Expiry {
+    /// Parses the expiry as RFC 3339, `YYYY-MM-DD HH:MM:SS` (UTC) or a bare
+    /// `YYYY-MM-DD` (midnight UTC). Returns `None` for anything else.
+    #[must_use]
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        let s = self.0.trim();
+        DateTime::parse_from_rfc3339(s)
+            .map(|t| t.with_timezone(&Utc))
+            .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").map(|t| t.and_utc()))
+            .or_else(|_| {
+                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .map(|d| d.and_time(NaiveTime::MIN).and_utc())
+            })
+            .ok()
+    }
+
+    /// Whole days from now until expiry; negative once expired.
+    #[must_use]
+    pub fn days_until_expiry(&self) -> Option<i64> {
+        self.timestamp().map(|t| (t - Utc::now()).num_days())
+    }
+
+    /// Whether the expiry falls before now + `within` (including already
+    /// expired). `false` if the date cannot be parsed.
+    #[must_use]
+    pub fn is_expiring_within(&self, within: std::time::Duration) -> bool {
+        let within = TimeDelta::from_std(within).unwrap_or(TimeDelta::MAX);
+        self.timestamp().is_some_and(|t| {
+            Utc::now()
+                .checked_add_signed(within)
+                .is_none_or(|limit| t <= limit)
+        })
+    }
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::Duration;
+
+    fn expiry(s: &str) -> Expiry {
+        Expiry(s.to_owned())
+    }
+
+    #[test]
+    fn parses_rfc3339_in_utc() {
+        assert_eq!(
+            expiry("2030-05-01T12:30:00+02:00").timestamp(),
+            Utc.with_ymd_and_hms(2030, 5, 1, 10, 30, 0).single()
+        );
+    }
+
+    #[test]
+    fn parses_date_and_time_as_utc() {
+        assert_eq!(
+            expiry("2030-05-01 12:30:00").timestamp(),
+            Utc.with_ymd_and_hms(2030, 5, 1, 12, 30, 0).single()
+        );
+    }
+
+    #[test]
+    fn parses_bare_date_as_midnight() {
+        assert_eq!(
+            expiry(" 2030-05-01 ").timestamp(),
+            Utc.with_ymd_and_hms(2030, 5, 1, 0, 0, 0).single()
+        );
+    }
+
+    #[test]
+    fn unparseable_dates_give_none() {
+        for s in ["", "never", "01/05/2030", "2030-13-01"] {
+            let e = expiry(s);
+            assert_eq!(e.timestamp(), None, "{s}");
+            assert_eq!(e.days_until_expiry(), None, "{s}");
+            assert!(!e.is_expiring_within(Duration::MAX), "{s}");
+        }
+    }
+
+    #[test]
+    fn expiring_within_handles_huge_durations() {
+        let far = expiry("9999-12-31");
+        assert!(far.is_expiring_within(Duration::MAX));
+        assert!(far.is_expiring_within(Duration::from_secs(u64::MAX / 2)));
+        assert!(!far.is_expiring_within(Duration::from_hours(24)));
+        assert!(expiry("2000-01-01").is_expiring_within(Duration::ZERO));
+    }
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::client::NjallaClient;
+use crate::error::Result;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Forward {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ForwardsResponse {
+    forwards: Vec<Forward>,
+}
+
+impl NjallaClient {
+    /// Lists all email forwards for a domain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or API rejection.
+    pub async fn list_forwards(&self, domain: &str) -> Result<Vec<Forward>> {
+        let resp: ForwardsResponse = self
+            .call("list-forwards", json!({ "domain": domain }))
+            .await?;
+        Ok(resp.forwards)
+    }
+
+    /// Adds an email forward. `from` is the local part, `to` the target address.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the forward is invalid.
+    pub async fn add_forward(&self, domain: &str, from: &str, to: &str) -> Result<Forward> {
+        self.call(
+            "add-forward",
+            json!({ "domain": domain, "from": from, "to": to }),
+        )
+        .await
+    }
+
+    /// Removes an email forward from a domain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the forward is not found.
+    pub async fn remove_forward(&self, domain: &str, from: &str, to: &str) -> Result<()> {
+        self.call_void(
+            "remove-forward",
+            json!({ "domain": domain, "from": from, "to": to }),
+        )
+        .await
+    }
+}
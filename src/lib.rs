@@ -1,11 +1,34 @@
+pub mod batch;
 pub mod client;
+pub mod dnssec;
 pub mod domain;
 pub mod error;
+pub mod expiry;
+pub mod forward;
+mod limit;
 pub mod record;
+pub mod record_type;
+pub mod retry;
 pub mod server;
+pub mod status;
+pub mod task;
+pub mod token;
+pub mod vpn;
+pub mod wallet;
 
-pub use client::NjallaClient;
-pub use domain::{Domain, MarketDomain};
-pub use error::Error;
-pub use record::{NewRecord, Record};
-pub use server::{NewServer, Server};
+pub use batch::{Batch, BatchCall, BatchResults};
+pub use client::{NjallaClient, NjallaClientBuilder};
+pub use dnssec::{DnsKey, Dnssec, DsRecord};
+pub use domain::{Domain, DomainUpdate, Glue, MarketDomain, Tld};
+pub use error::{Error, FieldError};
+pub use expiry::Expiry;
+pub use forward::Forward;
+pub use record::{DynamicRecord, NewRecord, Record, RedirectCode};
+pub use record_type::{Caa, RecordContent, RecordType, Srv, Tlsa};
+pub use retry::RetryPolicy;
+pub use server::{NewServer, ReverseDnsCheck, Server, ServerUpdate};
+pub use status::{DomainStatus, OsState, ServerStatus, TaskStatus};
+pub use task::TaskHandle;
+pub use token::{NewToken, Token};
+pub use vpn::{NewVpn, Vpn, VpnProtocol, VpnUpdate};
+pub use wallet::{Amount, Payment, PaymentMethod, Transaction};
@@ -0,0 +1,147 @@
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio::time::Instant;
+
+/// Client-side request throttling, shared by all clones of a client.
+#[derive(Debug, Default)]
+pub struct Limiter {
+    bucket: Option<Mutex<Bucket>>,
+    in_flight: Option<Semaphore>,
+}
+
+/// Token bucket allowing bursts of `capacity` requests, refilled at `rate`
+/// tokens per second.
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    rate: f64,
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl Bucket {
+    /// Takes a token, or returns how long until one is available.
+    fn take(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+        let elapsed = (now - self.refilled).as_secs_f64();
+        self.tokens = elapsed.mul_add(self.rate, self.tokens).min(self.capacity);
+        self.refilled = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            // Saturates for rates so low the wait does not fit a `Duration`.
+            Some(
+                Duration::try_from_secs_f64((1.0 - self.tokens) / self.rate)
+                    .unwrap_or(Duration::MAX),
+            )
+        }
+    }
+}
+
+impl Limiter {
+    /// `rate_limit` is `(requests, per)`; both limits are off when `None`.
+    pub fn new(rate_limit: Option<(u32, Duration)>, max_in_flight: Option<usize>) -> Self {
+        Self {
+            bucket: rate_limit.map(|(requests, per)| {
+                let capacity = f64::from(requests);
+                Mutex::new(Bucket {
+                    capacity,
+                    rate: capacity / per.as_secs_f64(),
+                    tokens: capacity,
+                    refilled: Instant::now(),
+                })
+            }),
+            in_flight: max_in_flight.map(Semaphore::new),
+        }
+    }
+
+    /// Waits for a free request slot and a rate limit token. The slot is
+    /// released when the returned permit is dropped.
+    pub async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+        let permit = match &self.in_flight {
+            // The semaphore is never closed, so this only fails in theory.
+            Some(semaphore) => semaphore.acquire().await.ok(),
+            None => None,
+        };
+        if let Some(bucket) = &self.bucket {
+            loop {
+                let wait = bucket.lock().unwrap_or_else(PoisonError::into_inner).take();
+                match wait {
+                    Some(wait) => tokio::time::sleep(wait).await,
+                    None => break,
+                }
+            }
+        }
+        permit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn take(limiter: &Limiter) -> Option<Duration> {
+        limiter
+            .bucket
+            .as_ref()
+            .and_then(|b| b.lock().unwrap_or_else(PoisonError::into_inner).take())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bucket_allows_a_burst_then_refills_at_rate() {
+        let limiter = Limiter::new(Some((4, Duration::from_secs(2))), None);
+        for _ in 0..4 {
+            assert_eq!(take(&limiter), None);
+        }
+        assert_eq!(take(&limiter), Some(Duration::from_millis(500)));
+
+        tokio::time::advance(Duration::from_millis(250)).await;
+        assert_eq!(take(&limiter), Some(Duration::from_millis(250)));
+
+        tokio::time::advance(Duration::from_millis(250)).await;
+        assert_eq!(take(&limiter), None);
+        assert!(take(&limiter).is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bucket_refill_is_capped_at_capacity() {
+        let limiter = Limiter::new(Some((2, Duration::from_secs(1))), None);
+        tokio::time::advance(Duration::from_mins(1)).await;
+        assert_eq!(take(&limiter), None);
+        assert_eq!(take(&limiter), None);
+        assert!(take(&limiter).is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn acquire_waits_for_a_token() {
+        let limiter = Limiter::new(Some((1, Duration::from_secs(1))), None);
+        let start = Instant::now();
+        drop(limiter.acquire().await);
+        drop(limiter.acquire().await);
+        assert!(Instant::now() - start >= Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn extreme_periods_do_not_panic() {
+        let limiter = Limiter::new(Some((1, Duration::MAX)), None);
+        assert_eq!(take(&limiter), None);
+        assert!(take(&limiter).is_some_and(|wait| wait > Duration::from_secs(1 << 40)));
+    }
+
+    #[tokio::test]
+    async fn permits_bound_requests_in_flight() {
+        let limiter = Limiter::new(None, Some(2));
+        let available = || limiter.in_flight.as_ref().map(Semaphore::available_permits);
+        let first = limiter.acquire().await;
+        let second = limiter.acquire().await;
+        assert!(first.is_some() && second.is_some());
+        assert_eq!(available(), Some(0));
+        drop(first);
+        assert_eq!(available(), Some(1));
+        drop(second);
+        assert_eq!(available(), Some(2));
+        assert!(Limiter::default().acquire().await.is_none());
+    }
+}
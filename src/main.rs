@@ -1,9 +1,41 @@
-use clap::{Parser, Subcommand};
-use njalla::{NewRecord, NewServer, NjallaClient};
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::io::Write;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use njalla::{
+    Amount, DnsKey, DomainUpdate, DsRecord, Expiry, Glue, NewRecord, NewServer, NewToken, NewVpn,
+    NjallaClient, PaymentMethod, RecordType, RedirectCode, RetryPolicy, Server, ServerStatus,
+    ServerUpdate, TaskHandle, Token, VpnProtocol, VpnUpdate,
+};
 
 #[derive(Debug, Parser)]
 #[command(name = "njalla", about = "cli toolkit for njal.la")]
 struct Cli {
+    #[arg(
+        long,
+        global = true,
+        help = "api endpoint (default: $NJALLA_ENDPOINT or njal.la)"
+    )]
+    endpoint: Option<String>,
+    #[arg(
+        long,
+        global = true,
+        help = "proxy url, e.g. socks5h://127.0.0.1:9050 for tor (default: $NJALLA_PROXY)"
+    )]
+    proxy: Option<String>,
+    #[arg(
+        long,
+        global = true,
+        help = "retries for read-only calls on network errors, 429 and 5xx (default: 3)"
+    )]
+    retries: Option<u32>,
+    #[arg(long, global = true, help = "max api requests per second")]
+    rate_limit: Option<u32>,
+    #[arg(long, global = true, help = "max api requests in flight at once")]
+    max_in_flight: Option<usize>,
     #[command(subcommand)]
     cmd: Cmd,
 }
@@ -14,8 +46,16 @@ enum Cmd {
     Domain(DomainCmd),
     #[command(subcommand, about = "dns record operations")]
     Record(RecordCmd),
+    #[command(subcommand, about = "email forward operations")]
+    Forward(ForwardCmd),
     #[command(subcommand, about = "server operations")]
     Server(ServerCmd),
+    #[command(subcommand, about = "api token operations")]
+    Token(TokenCmd),
+    #[command(subcommand, about = "vpn operations")]
+    Vpn(VpnCmd),
+    #[command(subcommand, about = "wallet operations")]
+    Wallet(WalletCmd),
 }
 
 #[derive(Debug, Subcommand)]
@@ -24,16 +64,115 @@ enum DomainCmd {
     List,
     #[command(about = "get domain details")]
     Get { domain: String },
+    #[command(about = "edit domain settings")]
+    Edit {
+        domain: String,
+        #[arg(short, long = "nameserver")]
+        nameservers: Vec<String>,
+        #[arg(short, long)]
+        lock: Option<bool>,
+        #[arg(short, long)]
+        mailforwarding: Option<bool>,
+        #[arg(short, long)]
+        autorenew: Option<bool>,
+    },
     #[command(about = "search available domains")]
     Find { query: String },
+    #[command(about = "list registrable tlds and prices")]
+    Tlds {
+        #[arg(short, long, value_enum, default_value = "name")]
+        sort: TldSort,
+        #[arg(long, help = "only tlds costing at most this much per year")]
+        max_price: Option<i64>,
+        #[arg(long, help = "only tlds costing at least this much per year")]
+        min_price: Option<i64>,
+        #[arg(long, help = "only tlds supporting dnssec")]
+        dnssec: bool,
+    },
     #[command(about = "register a new domain")]
     Register {
         domain: String,
         #[arg(default_value = "1")]
         years: u32,
+        #[arg(short, long, help = "poll the registration task until it finishes")]
+        wait: bool,
     },
+    #[command(about = "renew a domain")]
+    Renew {
+        domain: String,
+        #[arg(default_value = "1")]
+        years: u32,
+        #[arg(short, long, help = "poll the renewal task until it finishes")]
+        wait: bool,
+    },
+    #[command(subcommand, about = "transfer a domain in or out")]
+    Transfer(TransferCmd),
     #[command(about = "check async task status")]
-    CheckTask { id: String },
+    CheckTask {
+        id: String,
+        #[arg(short, long, help = "poll until the task finishes")]
+        wait: bool,
+    },
+    #[command(subcommand, about = "dnssec operations")]
+    Dnssec(DnssecCmd),
+    #[command(subcommand, about = "glue record operations")]
+    Glue(GlueCmd),
+}
+
+#[derive(Debug, Subcommand)]
+enum TransferCmd {
+    #[command(about = "transfer a domain in from another registrar")]
+    In {
+        domain: String,
+        auth_code: String,
+        #[arg(short, long, help = "poll the transfer task until it finishes")]
+        wait: bool,
+    },
+    #[command(about = "unlock a domain and print its auth code")]
+    Out { domain: String },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum TldSort {
+    Name,
+    Price,
+    Renewal,
+}
+
+#[derive(Debug, Subcommand)]
+enum DnssecCmd {
+    #[command(about = "list dnssec entries for a domain")]
+    List { domain: String },
+    #[command(subcommand, about = "add a ds record or dnskey")]
+    Add(DnssecAddCmd),
+    #[command(about = "remove a dnssec entry")]
+    Remove { domain: String, id: String },
+}
+
+#[derive(Debug, Subcommand)]
+enum DnssecAddCmd {
+    #[command(about = "add a ds record")]
+    Ds {
+        domain: String,
+        #[arg(short, long)]
+        key_tag: u16,
+        #[arg(short, long)]
+        algorithm: u8,
+        #[arg(short = 't', long)]
+        digest_type: u8,
+        #[arg(short, long)]
+        digest: String,
+    },
+    #[command(about = "add a dnskey")]
+    Key {
+        domain: String,
+        #[arg(short, long, default_value = "257")]
+        flags: u16,
+        #[arg(short, long)]
+        algorithm: u8,
+        #[arg(short, long)]
+        public_key: String,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -45,8 +184,12 @@ enum RecordCmd {
         domain: String,
         #[arg(short, long)]
         name: String,
-        #[arg(short = 't', long = "type")]
-        record_type: String,
+        #[arg(
+            short = 't',
+            long = "type",
+            help = "record type, or other:<TYPE> for types not listed (e.g. other:HTTPS)"
+        )]
+        record_type: RecordType,
         #[arg(short, long)]
         content: String,
         #[arg(long, default_value = "3600")]
@@ -60,8 +203,12 @@ enum RecordCmd {
         id: String,
         #[arg(short, long)]
         name: Option<String>,
-        #[arg(short = 't', long = "type")]
-        record_type: Option<String>,
+        #[arg(
+            short = 't',
+            long = "type",
+            help = "record type, or other:<TYPE> for types not listed (e.g. other:HTTPS)"
+        )]
+        record_type: Option<RecordType>,
         #[arg(short, long)]
         content: Option<String>,
         #[arg(long)]
@@ -69,10 +216,51 @@ enum RecordCmd {
         #[arg(short, long)]
         priority: Option<u32>,
     },
+    #[command(about = "add an http redirect record")]
+    Redirect {
+        domain: String,
+        #[arg(short, long)]
+        name: String,
+        #[arg(short, long, help = "absolute http(s) url to redirect to")]
+        to: String,
+        #[arg(short, long, default_value = "301", help = "301 or 302")]
+        code: RedirectCode,
+        #[arg(long, default_value = "3600")]
+        ttl: u32,
+    },
+    #[command(about = "print the update url for a dynamic record")]
+    DdnsUrl {
+        domain: String,
+        id: String,
+        #[arg(long, help = "pin this address instead of using the caller's")]
+        ip: Option<IpAddr>,
+    },
     #[command(about = "remove a dns record")]
     Remove { domain: String, id: String },
 }
 
+#[derive(Debug, Subcommand)]
+enum ForwardCmd {
+    #[command(about = "list email forwards for a domain")]
+    List { domain: String },
+    #[command(about = "add an email forward")]
+    Add {
+        domain: String,
+        #[arg(short, long)]
+        from: String,
+        #[arg(short, long)]
+        to: String,
+    },
+    #[command(about = "remove an email forward")]
+    Remove {
+        domain: String,
+        #[arg(short, long)]
+        from: String,
+        #[arg(short, long)]
+        to: String,
+    },
+}
+
 #[derive(Debug, Subcommand)]
 enum ServerCmd {
     #[command(about = "list all servers")]
@@ -92,13 +280,60 @@ enum ServerCmd {
         ssh_key: String,
         #[arg(short, long, default_value = "1")]
         months: u32,
+        #[arg(short, long, help = "wait until the server is running")]
+        wait: bool,
     },
     #[command(about = "stop a server")]
-    Stop { id: String },
+    Stop {
+        id: String,
+        #[arg(short, long, help = "wait until the server is stopped")]
+        wait: bool,
+    },
     #[command(about = "start a server")]
-    Start { id: String },
+    Start {
+        id: String,
+        #[arg(short, long, help = "wait until the server is running")]
+        wait: bool,
+    },
     #[command(about = "restart a server")]
-    Restart { id: String },
+    Restart {
+        id: String,
+        #[arg(short, long, help = "wait until the server is running again")]
+        wait: bool,
+    },
+    #[command(about = "edit a server")]
+    Edit {
+        id: String,
+        #[arg(short, long)]
+        name: Option<String>,
+        #[arg(short, long)]
+        autorenew: Option<bool>,
+        #[arg(short, long)]
+        ssh_key: Option<String>,
+        #[arg(short, long)]
+        reverse_name: Option<String>,
+    },
+    #[command(about = "renew a server")]
+    Renew {
+        id: String,
+        #[arg(default_value = "1")]
+        months: u32,
+        #[arg(short, long, help = "poll the renewal task until it finishes")]
+        wait: bool,
+    },
+    #[command(about = "show or set reverse dns, with forward-confirmed check")]
+    Rdns {
+        id: String,
+        #[arg(short, long, help = "set this ptr name before checking")]
+        set: Option<String>,
+        #[arg(
+            short,
+            long,
+            requires = "set",
+            help = "ip to set (default: all server ips)"
+        )]
+        ip: Option<IpAddr>,
+    },
     #[command(about = "factory reset a server (destroys data)")]
     Reset {
         id: String,
@@ -108,17 +343,256 @@ enum ServerCmd {
         ssh_key: String,
         #[arg(short = 't', long = "type")]
         server_type: String,
+        #[arg(short, long, help = "wait until the server is running again")]
+        wait: bool,
     },
     #[command(about = "remove a server (destroys data)")]
     Remove { id: String },
 }
 
-fn dump<T: serde::Serialize>(val: &T) -> njalla::error::Result<()> {
+#[derive(Debug, Subcommand)]
+enum GlueCmd {
+    #[command(about = "list glue records for a domain")]
+    List { domain: String },
+    #[command(
+        about = "add a glue record",
+        group(ArgGroup::new("address").required(true).multiple(true).args(["ipv4", "ipv6"]))
+    )]
+    Add {
+        domain: String,
+        name: String,
+        #[arg(short = '4', long)]
+        ipv4: Option<Ipv4Addr>,
+        #[arg(short = '6', long)]
+        ipv6: Option<Ipv6Addr>,
+    },
+    #[command(about = "edit a glue record")]
+    Edit {
+        domain: String,
+        name: String,
+        #[arg(short = '4', long)]
+        ipv4: Option<Ipv4Addr>,
+        #[arg(short = '6', long)]
+        ipv6: Option<Ipv6Addr>,
+    },
+    #[command(about = "remove a glue record")]
+    Remove { domain: String, name: String },
+}
+
+#[derive(Debug, Subcommand)]
+enum TokenCmd {
+    #[command(about = "list api tokens")]
+    List,
+    #[command(about = "create an api token")]
+    Add {
+        #[arg(short, long, default_value = "")]
+        comment: String,
+        #[arg(short, long, help = "allowed source ip (repeatable)")]
+        from: Vec<IpAddr>,
+        #[arg(short, long, help = "allowed api method (repeatable)")]
+        acl: Vec<String>,
+    },
+    #[command(about = "edit an api token")]
+    Edit {
+        key: String,
+        #[arg(short, long)]
+        comment: Option<String>,
+        #[arg(short, long, help = "allowed source ip (repeatable, replaces list)")]
+        from: Vec<IpAddr>,
+        #[arg(short, long, help = "allowed api method (repeatable, replaces list)")]
+        acl: Vec<String>,
+    },
+    #[command(about = "remove an api token")]
+    Remove { key: String },
+    #[command(about = "replace the current token with a fresh one")]
+    Rotate {
+        #[arg(
+            long,
+            help = "rotate even if the token is not set in ~/.config/njalla/.env"
+        )]
+        force: bool,
+    },
+}
+
+/// Where `NJALLA_API_TOKEN` is set. Must be checked before `.env` files are
+/// loaded, since those only fill in variables that are not set yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenSource {
+    Shell,
+    DotEnv,
+    Config,
+}
+
+impl TokenSource {
+    fn detect() -> Self {
+        if std::env::var_os("NJALLA_API_TOKEN").is_some() {
+            return Self::Shell;
+        }
+        let in_dotenv = dotenvy::dotenv_iter().is_ok_and(|mut vars| {
+            vars.any(|var| var.is_ok_and(|(key, _)| key == "NJALLA_API_TOKEN"))
+        });
+        if in_dotenv {
+            Self::DotEnv
+        } else {
+            Self::Config
+        }
+    }
+}
+
+impl std::fmt::Display for TokenSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Shell => "the environment",
+            Self::DotEnv => "a local .env file",
+            Self::Config => "the config file",
+        })
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum VpnCmd {
+    #[command(about = "list all vpns")]
+    List,
+    #[command(about = "get vpn details")]
+    Get { id: String },
+    #[command(about = "add a new vpn")]
+    Add {
+        name: String,
+        #[arg(short, long, default_value = "1")]
+        months: u32,
+        #[arg(short, long)]
+        autorenew: bool,
+    },
+    #[command(about = "edit a vpn")]
+    Edit {
+        id: String,
+        #[arg(short, long)]
+        name: Option<String>,
+        #[arg(short, long)]
+        autorenew: Option<bool>,
+        #[arg(short, long)]
+        publickey: Option<String>,
+    },
+    #[command(about = "renew a vpn")]
+    Renew {
+        id: String,
+        #[arg(default_value = "1")]
+        months: u32,
+        #[arg(short, long, help = "poll the renewal task until it finishes")]
+        wait: bool,
+    },
+    #[command(about = "remove a vpn")]
+    Remove { id: String },
+    #[command(about = "export a client config (stdout unless --output)")]
+    Config {
+        id: String,
+        #[arg(
+            short,
+            long,
+            default_value = "wireguard",
+            help = "wireguard or openvpn"
+        )]
+        protocol: VpnProtocol,
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum WalletCmd {
+    #[command(about = "show wallet balance")]
+    Balance,
+    #[command(about = "refill the wallet")]
+    Pay {
+        amount: i64,
+        #[arg(
+            short,
+            long,
+            help = "paypal, bitcoin, litecoin, monero, zcash or ethereum"
+        )]
+        via: PaymentMethod,
+    },
+    #[command(about = "get payment details")]
+    Payment { id: String },
+    #[command(about = "list wallet transactions")]
+    Transactions,
+}
+
+fn dump<T: Serialize>(val: &T) -> njalla::error::Result<()> {
     let s = serde_json::to_string_pretty(val)?;
     println!("{s}");
     Ok(())
 }
 
+/// An item dumped with a human-readable `expires_in` next to its fields.
+#[derive(Debug, Serialize)]
+struct WithExpiry<'a, T> {
+    #[serde(flatten)]
+    item: &'a T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_in: Option<String>,
+}
+
+impl<'a, T> WithExpiry<'a, T> {
+    fn new(item: &'a T, expiry: &Expiry) -> Self {
+        Self {
+            item,
+            expires_in: relative_expiry(expiry),
+        }
+    }
+}
+
+fn with_expiry<T>(items: &[T], expiry: fn(&T) -> &Expiry) -> Vec<WithExpiry<'_, T>> {
+    items
+        .iter()
+        .map(|item| WithExpiry::new(item, expiry(item)))
+        .collect()
+}
+
+#[cfg(feature = "chrono")]
+fn relative_expiry(expiry: &Expiry) -> Option<String> {
+    expiry.days_until_expiry().map(|days| match days {
+        0 => "today".into(),
+        1 => "in 1 day".into(),
+        -1 => "1 day ago".into(),
+        d if d > 0 => format!("in {d} days"),
+        d => format!("{} days ago", -d),
+    })
+}
+
+#[cfg(not(feature = "chrono"))]
+#[allow(clippy::missing_const_for_fn)]
+fn relative_expiry(_: &Expiry) -> Option<String> {
+    None
+}
+
+const TASK_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const TASK_TIMEOUT: Duration = Duration::from_mins(30);
+const SERVER_TIMEOUT: Duration = Duration::from_mins(15);
+
+/// Prints how to poll a started task, or with `wait` polls it to completion.
+async fn finish_task(task: TaskHandle, wait: bool, what: &str) -> njalla::error::Result<()> {
+    if wait {
+        eprintln!("{what} task started: {task}");
+        wait_task(&task).await
+    } else {
+        println!("{what} task started: {task}");
+        println!("poll with: njalla domain check-task {task} --wait");
+        Ok(())
+    }
+}
+
+/// Polls a task until it finishes, showing each pending status on stderr.
+async fn wait_task(task: &TaskHandle) -> njalla::error::Result<()> {
+    let status = task
+        .wait_with_progress(TASK_TIMEOUT, TASK_POLL_INTERVAL, |status| {
+            eprintln!("task {task}: {status}");
+        })
+        .await?;
+    println!("{status}");
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() {
     let cli = match Cli::try_parse() {
@@ -136,42 +610,224 @@ async fn main() {
         }
     };
 
-    let client = match NjallaClient::from_env() {
+    let token_source = TokenSource::detect();
+    let client = NjallaClient::builder().load_env().and_then(|mut builder| {
+        if let Some(endpoint) = cli.endpoint {
+            builder = builder.endpoint(endpoint);
+        }
+        if let Some(proxy) = cli.proxy {
+            builder = builder.proxy(proxy);
+        }
+        if let Some(retries) = cli.retries {
+            builder = builder.retry(RetryPolicy::default().max_retries(retries));
+        }
+        if let Some(rate_limit) = cli.rate_limit {
+            builder = builder.rate_limit(rate_limit, Duration::from_secs(1));
+        }
+        if let Some(max_in_flight) = cli.max_in_flight {
+            builder = builder.max_in_flight(max_in_flight);
+        }
+        builder.build()
+    });
+    let client = match client {
         Ok(c) => c,
         Err(e) => {
             eprintln!("failed to init client: {e}");
-            eprintln!("set NJALLA_API_TOKEN in .env or environment");
+            if matches!(e, njalla::Error::Env(_)) {
+                eprintln!("set NJALLA_API_TOKEN in .env or environment");
+            }
             std::process::exit(1);
         }
     };
 
-    if let Err(e) = run(cli.cmd, &client).await {
+    if let Err(e) = run(cli.cmd, &client, token_source).await {
         eprintln!("error: {e}");
         std::process::exit(1);
     }
 }
 
-async fn run(cmd: Cmd, client: &NjallaClient) -> njalla::error::Result<()> {
+async fn run(
+    cmd: Cmd,
+    client: &NjallaClient,
+    token_source: TokenSource,
+) -> njalla::error::Result<()> {
     match cmd {
         Cmd::Domain(sub) => run_domain(sub, client).await,
         Cmd::Record(sub) => run_record(sub, client).await,
+        Cmd::Forward(sub) => run_forward(sub, client).await,
         Cmd::Server(sub) => run_server(sub, client).await,
+        Cmd::Token(sub) => run_token(sub, client, token_source).await,
+        Cmd::Vpn(sub) => run_vpn(sub, client).await,
+        Cmd::Wallet(sub) => run_wallet(sub, client).await,
     }
 }
 
 async fn run_domain(cmd: DomainCmd, client: &NjallaClient) -> njalla::error::Result<()> {
     match cmd {
-        DomainCmd::List => dump(&client.list_domains().await?)?,
-        DomainCmd::Get { domain } => dump(&client.get_domain(&domain).await?)?,
+        DomainCmd::List => {
+            let domains = client.list_domains().await?;
+            dump(&with_expiry(&domains, |d| &d.expiry))?;
+        }
+        DomainCmd::Get { domain } => {
+            let domain = client.get_domain(&domain).await?;
+            dump(&WithExpiry::new(&domain, &domain.expiry))?;
+        }
+        DomainCmd::Edit {
+            domain,
+            nameservers,
+            lock,
+            mailforwarding,
+            autorenew,
+        } => {
+            let update = DomainUpdate {
+                nameservers: (!nameservers.is_empty()).then_some(nameservers),
+                locked: lock,
+                mailforwarding,
+                autorenew,
+            };
+            client.edit_domain(&domain, &update).await?;
+            println!("domain updated");
+        }
         DomainCmd::Find { query } => dump(&client.find_domains(&query).await?)?,
-        DomainCmd::Register { domain, years } => {
+        DomainCmd::Tlds {
+            sort,
+            max_price,
+            min_price,
+            dnssec,
+        } => {
+            let mut tlds: Vec<_> = client
+                .list_tlds()
+                .await?
+                .into_iter()
+                .filter(|t| max_price.is_none_or(|max| t.price.0 <= max))
+                .filter(|t| min_price.is_none_or(|min| t.price.0 >= min))
+                .filter(|t| !dnssec || t.dnssec)
+                .collect();
+            match sort {
+                TldSort::Name => {}
+                TldSort::Price => tlds.sort_by_key(|t| t.price),
+                TldSort::Renewal => tlds.sort_by_key(|t| t.renewal_price),
+            }
+            dump(&tlds)?;
+        }
+        DomainCmd::Register {
+            domain,
+            years,
+            wait,
+        } => {
             let task = client.register_domain(&domain, years).await?;
-            println!("registration task started: {task}");
-            println!("poll with: njalla domain check-task {task}");
+            finish_task(task, wait, "registration").await?;
+        }
+        DomainCmd::Renew {
+            domain,
+            years,
+            wait,
+        } => {
+            let task = client.renew_domain(&domain, years).await?;
+            finish_task(task, wait, "renewal").await?;
+        }
+        DomainCmd::Transfer(TransferCmd::In {
+            domain,
+            auth_code,
+            wait,
+        }) => {
+            let task = client.import_domain(&domain, &auth_code).await?;
+            finish_task(task, wait, "transfer").await?;
+        }
+        DomainCmd::Transfer(TransferCmd::Out { domain }) => {
+            println!("{}", client.get_auth_code(&domain).await?);
+        }
+        DomainCmd::CheckTask { id, wait } => {
+            if wait {
+                wait_task(&client.task(id)).await?;
+            } else {
+                println!("{}", client.check_task(&id).await?);
+            }
+        }
+        DomainCmd::Dnssec(sub) => run_dnssec(sub, client).await?,
+        DomainCmd::Glue(sub) => run_glue(sub, client).await?,
+    }
+    Ok(())
+}
+
+async fn run_dnssec(cmd: DnssecCmd, client: &NjallaClient) -> njalla::error::Result<()> {
+    match cmd {
+        DnssecCmd::List { domain } => dump(&client.list_dnssec(&domain).await?)?,
+        DnssecCmd::Add(DnssecAddCmd::Ds {
+            domain,
+            key_tag,
+            algorithm,
+            digest_type,
+            digest,
+        }) => {
+            let ds = DsRecord {
+                key_tag,
+                algorithm,
+                digest_type,
+                digest,
+            };
+            dump(&client.add_ds(&domain, &ds).await?)?;
+        }
+        DnssecCmd::Add(DnssecAddCmd::Key {
+            domain,
+            flags,
+            algorithm,
+            public_key,
+        }) => {
+            let key = DnsKey {
+                flags,
+                algorithm,
+                public_key,
+            };
+            dump(&client.add_dnskey(&domain, &key).await?)?;
+        }
+        DnssecCmd::Remove { domain, id } => {
+            client.remove_dnssec(&domain, &id).await?;
+            println!("dnssec entry {id} removed");
+        }
+    }
+    Ok(())
+}
+
+async fn run_glue(cmd: GlueCmd, client: &NjallaClient) -> njalla::error::Result<()> {
+    match cmd {
+        GlueCmd::List { domain } => dump(&client.list_glue(&domain).await?)?,
+        GlueCmd::Add {
+            domain,
+            name,
+            ipv4,
+            ipv6,
+        } => {
+            let glue = Glue {
+                name,
+                address4: ipv4,
+                address6: ipv6,
+            };
+            client.add_glue(&domain, &glue).await?;
+            println!("glue {} added", glue.name);
         }
-        DomainCmd::CheckTask { id } => {
-            let status = client.check_task(&id).await?;
-            println!("{status}");
+        GlueCmd::Edit {
+            domain,
+            name,
+            ipv4,
+            ipv6,
+        } => {
+            let glue = client.list_glue(&domain).await?;
+            let existing = glue
+                .iter()
+                .find(|g| g.name == name)
+                .ok_or_else(|| njalla::Error::NotFound(format!("glue {name} in {domain}")))?;
+            let patched = Glue {
+                address4: ipv4.or(existing.address4),
+                address6: ipv6.or(existing.address6),
+                name,
+            };
+            client.edit_glue(&domain, &patched).await?;
+            println!("glue updated");
+        }
+        GlueCmd::Remove { domain, name } => {
+            client.remove_glue(&domain, &name).await?;
+            println!("glue {name} removed");
         }
     }
     Ok(())
@@ -194,7 +850,9 @@ async fn run_record(cmd: RecordCmd, client: &NjallaClient) -> njalla::error::Res
                 content,
                 ttl,
                 priority,
+                redirect_code: None,
             };
+            rec.validate()?;
             dump(&client.add_record(&domain, &rec).await?)?;
         }
         RecordCmd::Edit {
@@ -218,10 +876,39 @@ async fn run_record(cmd: RecordCmd, client: &NjallaClient) -> njalla::error::Res
                 content: content.unwrap_or_else(|| existing.content.clone()),
                 ttl: ttl.unwrap_or(existing.ttl),
                 priority: priority.or(existing.priority),
+                key: existing.key.clone(),
+                redirect_code: existing.redirect_code,
             };
+            patched.validate()?;
             client.edit_record(&domain, &patched).await?;
             println!("record updated");
         }
+        RecordCmd::Redirect {
+            domain,
+            name,
+            to,
+            code,
+            ttl,
+        } => {
+            let rec = NewRecord::redirect(name, &to, code, ttl)?;
+            rec.validate()?;
+            dump(&client.add_record(&domain, &rec).await?)?;
+        }
+        RecordCmd::DdnsUrl { domain, id, ip } => {
+            let dynamic = client
+                .list_dynamic_records(&domain)
+                .await?
+                .into_iter()
+                .find(|r| r.id.as_deref() == Some(&id))
+                .ok_or_else(|| {
+                    njalla::Error::NotFound(format!("dynamic record {id} in {domain}"))
+                })?;
+            match ip {
+                Some(IpAddr::V4(ip)) => println!("{}&a={ip}", dynamic.update_url),
+                Some(IpAddr::V6(ip)) => println!("{}&aaaa={ip}", dynamic.update_url),
+                None => println!("{}&auto", dynamic.update_url),
+            }
+        }
         RecordCmd::Remove { domain, id } => {
             client.remove_record(&domain, &id).await?;
             println!("record {id} removed");
@@ -230,9 +917,26 @@ async fn run_record(cmd: RecordCmd, client: &NjallaClient) -> njalla::error::Res
     Ok(())
 }
 
+async fn run_forward(cmd: ForwardCmd, client: &NjallaClient) -> njalla::error::Result<()> {
+    match cmd {
+        ForwardCmd::List { domain } => dump(&client.list_forwards(&domain).await?)?,
+        ForwardCmd::Add { domain, from, to } => {
+            dump(&client.add_forward(&domain, &from, &to).await?)?;
+        }
+        ForwardCmd::Remove { domain, from, to } => {
+            client.remove_forward(&domain, &from, &to).await?;
+            println!("forward {from}@{domain} -> {to} removed");
+        }
+    }
+    Ok(())
+}
+
 async fn run_server(cmd: ServerCmd, client: &NjallaClient) -> njalla::error::Result<()> {
     match cmd {
-        ServerCmd::List => dump(&client.list_servers().await?)?,
+        ServerCmd::List => {
+            let servers = client.list_servers().await?;
+            dump(&with_expiry(&servers, |s| &s.expiry))?;
+        }
         ServerCmd::Images => dump(&client.list_server_images().await?)?,
         ServerCmd::Types => dump(&client.list_server_types().await?)?,
         ServerCmd::Add {
@@ -241,6 +945,7 @@ async fn run_server(cmd: ServerCmd, client: &NjallaClient) -> njalla::error::Res
             os,
             ssh_key,
             months,
+            wait,
         } => {
             let srv = NewServer {
                 name,
@@ -249,24 +954,359 @@ async fn run_server(cmd: ServerCmd, client: &NjallaClient) -> njalla::error::Res
                 ssh_key,
                 months,
             };
-            dump(&client.add_server(&srv).await?)?;
+            let server = client.add_server(&srv).await?;
+            dump_server(client, server, wait.then_some(ServerStatus::Running)).await?;
+        }
+        ServerCmd::Stop { id, wait } => {
+            let server = client.stop_server(&id).await?;
+            dump_server(client, server, wait.then_some(ServerStatus::Stopped)).await?;
+        }
+        ServerCmd::Start { id, wait } => {
+            let server = client.start_server(&id).await?;
+            dump_server(client, server, wait.then_some(ServerStatus::Running)).await?;
+        }
+        ServerCmd::Restart { id, wait } => {
+            let server = client.restart_server(&id).await?;
+            dump_restarted_server(client, server, wait).await?;
+        }
+        ServerCmd::Edit {
+            id,
+            name,
+            autorenew,
+            ssh_key,
+            reverse_name,
+        } => {
+            let update = ServerUpdate {
+                name,
+                autorenew,
+                ssh_key,
+                reverse_name,
+            };
+            dump(&client.edit_server(&id, &update).await?)?;
         }
-        ServerCmd::Stop { id } => dump(&client.stop_server(&id).await?)?,
-        ServerCmd::Start { id } => dump(&client.start_server(&id).await?)?,
-        ServerCmd::Restart { id } => dump(&client.restart_server(&id).await?)?,
+        ServerCmd::Renew { id, months, wait } => {
+            let task = client.renew_server(&id, months).await?;
+            finish_task(task, wait, "renewal").await?;
+        }
+        ServerCmd::Rdns { id, set, ip } => run_rdns(client, &id, set, ip).await?,
         ServerCmd::Reset {
             id,
             os,
             ssh_key,
             server_type,
+            wait,
         } => {
-            dump(
-                &client
-                    .reset_server(&id, &os, &ssh_key, &server_type)
-                    .await?,
-            )?;
+            let server = client
+                .reset_server(&id, &os, &ssh_key, &server_type)
+                .await?;
+            dump_restarted_server(client, server, wait).await?;
         }
         ServerCmd::Remove { id } => dump(&client.remove_server(&id).await?)?,
     }
     Ok(())
 }
+
+/// Optionally sets the ptr name (on `ip`, or every server ip), then reports
+/// forward-confirmed reverse dns.
+async fn run_rdns(
+    client: &NjallaClient,
+    id: &str,
+    set: Option<String>,
+    ip: Option<IpAddr>,
+) -> njalla::error::Result<()> {
+    let checks = if let Some(name) = set {
+        let ips = match ip {
+            Some(ip) => vec![ip],
+            None => client
+                .list_servers()
+                .await?
+                .into_iter()
+                .find(|s| s.id == id)
+                .ok_or_else(|| njalla::Error::NotFound(format!("server {id}")))?
+                .ips
+                .iter()
+                .filter_map(|ip| ip.parse().ok())
+                .collect(),
+        };
+        let mut checks = Vec::with_capacity(ips.len());
+        for ip in ips {
+            client.set_reverse_name(id, ip, &name).await?;
+            checks.push(client.check_reverse_name(ip, &name).await?);
+        }
+        checks
+    } else {
+        client.check_reverse_dns(id).await?
+    };
+    for check in checks.iter().filter(|c| !c.confirmed) {
+        eprintln!(
+            "warning: {} -> {} does not resolve back",
+            check.ip, check.reverse_name
+        );
+    }
+    dump(&checks)
+}
+
+/// Dumps `server`, or with `wait` first waits for it to reach that status.
+async fn dump_server(
+    client: &NjallaClient,
+    server: Server,
+    wait: Option<ServerStatus>,
+) -> njalla::error::Result<()> {
+    let Some(desired) = wait else {
+        return dump(&server);
+    };
+    eprintln!("waiting for server {} to be {desired}", server.id);
+    dump(
+        &client
+            .wait_for_server(&server.id, desired, SERVER_TIMEOUT)
+            .await?,
+    )
+}
+
+/// Like `dump_server` after a restart or reset, where the server has to go
+/// down before waiting for it to run again means anything.
+async fn dump_restarted_server(
+    client: &NjallaClient,
+    server: Server,
+    wait: bool,
+) -> njalla::error::Result<()> {
+    if !wait {
+        return dump(&server);
+    }
+    eprintln!("waiting for server {} to restart", server.id);
+    dump(
+        &client
+            .wait_for_server_restart(&server.id, SERVER_TIMEOUT)
+            .await?,
+    )
+}
+
+async fn run_token(
+    cmd: TokenCmd,
+    client: &NjallaClient,
+    token_source: TokenSource,
+) -> njalla::error::Result<()> {
+    match cmd {
+        TokenCmd::List => dump(&client.list_tokens().await?)?,
+        TokenCmd::Add { comment, from, acl } => {
+            let token = NewToken { comment, from, acl };
+            let key = client.add_token(&token).await?;
+            println!("{key}");
+        }
+        TokenCmd::Edit {
+            key,
+            comment,
+            from,
+            acl,
+        } => {
+            let tokens = client.list_tokens().await?;
+            let existing = tokens
+                .iter()
+                .find(|t| t.key == key)
+                .ok_or_else(|| njalla::Error::NotFound(format!("token {key}")))?;
+            let patched = Token {
+                comment: comment.unwrap_or_else(|| existing.comment.clone()),
+                from: if from.is_empty() {
+                    existing.from.clone()
+                } else {
+                    from
+                },
+                acl: if acl.is_empty() {
+                    existing.acl.clone()
+                } else {
+                    acl
+                },
+                key,
+            };
+            client.edit_token(&patched).await?;
+            println!("token updated");
+        }
+        TokenCmd::Remove { key } => {
+            client.remove_token(&key).await?;
+            println!("token removed");
+        }
+        TokenCmd::Rotate { force } => rotate_token(client, token_source, force).await?,
+    }
+    Ok(())
+}
+
+/// Creates a new token with the same restrictions as the current one, checks
+/// it works, saves it to `~/.config/njalla/.env`, then removes the old one.
+///
+/// Refuses without `force` if the token is set elsewhere, since that source
+/// would keep the deleted token and shadow the config file.
+async fn rotate_token(
+    client: &NjallaClient,
+    source: TokenSource,
+    force: bool,
+) -> njalla::error::Result<()> {
+    if source != TokenSource::Config && !force {
+        return Err(njalla::Error::Invalid(format!(
+            "NJALLA_API_TOKEN is set in {source}, which rotate cannot update; \
+             move it to ~/.config/njalla/.env or pass --force and update it yourself"
+        )));
+    }
+    let old_key = std::env::var("NJALLA_API_TOKEN")?;
+    let path = NjallaClient::config_path()
+        .ok_or_else(|| njalla::Error::NotFound("HOME for config path".into()))?;
+
+    let tokens = client.list_tokens().await?;
+    let old = tokens
+        .iter()
+        .find(|t| t.key == old_key)
+        .ok_or_else(|| njalla::Error::NotFound("current token in token list".into()))?;
+
+    let new_key = client
+        .add_token(&NewToken {
+            comment: old.comment.clone(),
+            from: old.from.clone(),
+            acl: old.acl.clone(),
+        })
+        .await?;
+
+    let saved = match client.with_token(&new_key).list_tokens().await {
+        Ok(_) => write_token(&path, &new_key),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = saved {
+        // The old token is still in use, so don't leave the new one behind.
+        if let Err(cleanup) = client.remove_token(&new_key).await {
+            eprintln!("failed to remove unused new token {new_key}: {cleanup}");
+        }
+        return Err(e);
+    }
+    println!("new token verified and written to {}", path.display());
+
+    client.remove_token(&old_key).await?;
+    println!("old token removed");
+
+    if source != TokenSource::Config {
+        eprintln!("note: update NJALLA_API_TOKEN in {source}, it still holds the old token");
+    }
+    Ok(())
+}
+
+/// Replaces `NJALLA_API_TOKEN` in the env file, keeping other lines. The
+/// file is written to a temporary file first and renamed over the original,
+/// so a failed write never leaves it empty or partial.
+fn write_token(path: &Path, new_key: &str) -> njalla::error::Result<()> {
+    let existing = match std::fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut lines: Vec<String> = existing
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("NJALLA_API_TOKEN="))
+        .map(str::to_owned)
+        .collect();
+    lines.push(format!("NJALLA_API_TOKEN={new_key}"));
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let written = create_private(&tmp).and_then(|mut file| {
+        file.write_all(format!("{}\n", lines.join("\n")).as_bytes())?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|()| std::fs::rename(&tmp, path)) {
+        std::fs::remove_file(&tmp).ok();
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Creates or truncates `path`, readable only by the owner on unix. An
+/// existing file's permissions are tightened too.
+fn create_private(path: &Path) -> std::io::Result<std::fs::File> {
+    let mut opts = std::fs::OpenOptions::new();
+    opts.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(0o600);
+    }
+    let file = opts.open(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(file)
+}
+
+async fn run_vpn(cmd: VpnCmd, client: &NjallaClient) -> njalla::error::Result<()> {
+    match cmd {
+        VpnCmd::List => {
+            let vpns = client.list_vpns().await?;
+            dump(&with_expiry(&vpns, |v| &v.expiry))?;
+        }
+        VpnCmd::Get { id } => {
+            let vpn = client.get_vpn(&id).await?;
+            dump(&WithExpiry::new(&vpn, &vpn.expiry))?;
+        }
+        VpnCmd::Add {
+            name,
+            months,
+            autorenew,
+        } => {
+            let vpn = NewVpn {
+                name,
+                autorenew,
+                months,
+            };
+            dump(&client.add_vpn(&vpn).await?)?;
+        }
+        VpnCmd::Edit {
+            id,
+            name,
+            autorenew,
+            publickey,
+        } => {
+            let update = VpnUpdate {
+                name,
+                autorenew,
+                publickey,
+            };
+            dump(&client.edit_vpn(&id, &update).await?)?;
+        }
+        VpnCmd::Renew { id, months, wait } => {
+            let task = client.renew_vpn(&id, months).await?;
+            finish_task(task, wait, "renewal").await?;
+        }
+        VpnCmd::Remove { id } => {
+            client.remove_vpn(&id).await?;
+            println!("vpn {id} removed");
+        }
+        VpnCmd::Config {
+            id,
+            protocol,
+            output,
+        } => {
+            let config = client.get_vpn_config(&id, protocol).await?;
+            match output {
+                Some(path) => {
+                    // The config holds the client's private key.
+                    create_private(&path)?.write_all(config.as_bytes())?;
+                    println!("config written to {}", path.display());
+                }
+                None => print!("{config}"),
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run_wallet(cmd: WalletCmd, client: &NjallaClient) -> njalla::error::Result<()> {
+    match cmd {
+        WalletCmd::Balance => dump(&client.get_balance().await?)?,
+        WalletCmd::Pay { amount, via } => dump(&client.add_payment(Amount(amount), via).await?)?,
+        WalletCmd::Payment { id } => dump(&client.get_payment(&id).await?)?,
+        WalletCmd::Transactions => dump(&client.list_transactions().await?)?,
+    }
+    Ok(())
+}
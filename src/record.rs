@@ -1,8 +1,18 @@
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
 
 use crate::client::NjallaClient;
-use crate::error::Result;
+use crate::error::{Error, FieldError, Result};
+use crate::record_type::{Caa, RecordContent, RecordType, Srv, Tlsa};
+
+const DYNAMIC_UPDATE_URL: &str = "https://njal.la/update/";
+
+/// TTLs the API accepts, in seconds.
+pub const ALLOWED_TTLS: [u32; 7] = [60, 300, 900, 3600, 10_800, 21_600, 86_400];
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Record {
@@ -10,27 +20,357 @@ pub struct Record {
     pub id: Option<String>,
     pub name: String,
     #[serde(rename = "type")]
-    pub record_type: String,
+    pub record_type: RecordType,
     pub content: String,
     pub ttl: u32,
     #[serde(rename = "prio", skip_serializing_if = "Option::is_none")]
     pub priority: Option<u32>,
+    /// Update key, only present on `Dynamic` records.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// HTTP status, only present on `Redirect` records.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_code: Option<RedirectCode>,
+}
+
+/// HTTP status code a `Redirect` record answers with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "u16", into = "u16")]
+pub enum RedirectCode {
+    /// 301, cached by browsers.
+    Permanent,
+    /// 302, not cached.
+    Temporary,
+}
+
+impl From<RedirectCode> for u16 {
+    fn from(code: RedirectCode) -> Self {
+        match code {
+            RedirectCode::Permanent => 301,
+            RedirectCode::Temporary => 302,
+        }
+    }
+}
+
+impl TryFrom<u16> for RedirectCode {
+    type Error = Error;
+
+    fn try_from(code: u16) -> Result<Self> {
+        match code {
+            301 => Ok(Self::Permanent),
+            302 => Ok(Self::Temporary),
+            other => Err(Error::Invalid(format!(
+                "redirect code must be 301 or 302, got {other}"
+            ))),
+        }
+    }
+}
+
+impl FromStr for RedirectCode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let code: u16 = s
+            .parse()
+            .map_err(|_| Error::Invalid(format!("redirect code must be 301 or 302, got {s}")))?;
+        code.try_into()
+    }
+}
+
+impl fmt::Display for RedirectCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", u16::from(*self))
+    }
+}
+
+/// A `Dynamic` record with the endpoint devices call to update it.
+#[derive(Debug, Clone, Serialize)]
+pub struct DynamicRecord {
+    pub id: Option<String>,
+    pub fqdn: String,
+    pub content: String,
+    pub key: String,
+    /// Append `&auto` to use the caller's address, or `&a=<ipv4>` / `&aaaa=<ipv6>`.
+    pub update_url: String,
+}
+
+impl Record {
+    /// Checks the record locally before it is sent with `edit_record`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Validation` listing every invalid field.
+    pub fn validate(&self) -> Result<()> {
+        validate_fields(
+            &self.name,
+            &self.record_type,
+            &self.content,
+            self.ttl,
+            self.priority,
+        )
+    }
+
+    /// Parses `content` according to `record_type`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Invalid` if the content does not fit the type.
+    pub fn parsed_content(&self) -> Result<RecordContent> {
+        RecordContent::parse(&self.record_type, &self.content)
+    }
+
+    /// Fully qualified name of the record within `domain`.
+    #[must_use]
+    pub fn fqdn(&self, domain: &str) -> String {
+        match self.name.as_str() {
+            "" | "@" => domain.to_owned(),
+            name => format!("{name}.{domain}"),
+        }
+    }
+
+    /// Returns the dynamic update details if this is a `Dynamic` record.
+    #[must_use]
+    pub fn dynamic(&self, domain: &str) -> Option<DynamicRecord> {
+        if self.record_type != RecordType::Dynamic {
+            return None;
+        }
+        let key = self.key.clone()?;
+        let fqdn = self.fqdn(domain);
+        Some(DynamicRecord {
+            id: self.id.clone(),
+            update_url: format!("{DYNAMIC_UPDATE_URL}?h={fqdn}&k={key}"),
+            content: self.content.clone(),
+            fqdn,
+            key,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct NewRecord {
     pub name: String,
     #[serde(rename = "type")]
-    pub record_type: String,
+    pub record_type: RecordType,
     pub content: String,
     pub ttl: u32,
     #[serde(rename = "prio", skip_serializing_if = "Option::is_none")]
     pub priority: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_code: Option<RedirectCode>,
+}
+
+impl NewRecord {
+    /// Checks the record locally before it is sent with `add_record`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Validation` listing every invalid field.
+    pub fn validate(&self) -> Result<()> {
+        validate_fields(
+            &self.name,
+            &self.record_type,
+            &self.content,
+            self.ttl,
+            self.priority,
+        )
+    }
+
+    /// Builds a record from wire-format `content`, checking that it fits
+    /// `record_type` first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Invalid` if the content does not fit the type.
+    pub fn parse(
+        name: impl Into<String>,
+        record_type: RecordType,
+        content: &str,
+        ttl: u32,
+        priority: Option<u32>,
+    ) -> Result<Self> {
+        let content = RecordContent::parse(&record_type, content)?;
+        Ok(Self::typed(name, record_type, &content, ttl, priority))
+    }
+
+    /// Builds an `A` record.
+    #[must_use]
+    pub fn a(name: impl Into<String>, ip: Ipv4Addr, ttl: u32) -> Self {
+        Self::typed(name, RecordType::A, &RecordContent::A(ip), ttl, None)
+    }
+
+    /// Builds an `AAAA` record.
+    #[must_use]
+    pub fn aaaa(name: impl Into<String>, ip: Ipv6Addr, ttl: u32) -> Self {
+        Self::typed(name, RecordType::Aaaa, &RecordContent::Aaaa(ip), ttl, None)
+    }
+
+    /// Builds an `SRV` record.
+    #[must_use]
+    pub fn srv(name: impl Into<String>, priority: u32, srv: Srv, ttl: u32) -> Self {
+        Self::typed(
+            name,
+            RecordType::Srv,
+            &RecordContent::Srv(srv),
+            ttl,
+            Some(priority),
+        )
+    }
+
+    /// Builds a `CAA` record.
+    #[must_use]
+    pub fn caa(name: impl Into<String>, caa: Caa, ttl: u32) -> Self {
+        Self::typed(name, RecordType::Caa, &RecordContent::Caa(caa), ttl, None)
+    }
+
+    /// Builds a `TLSA` record.
+    #[must_use]
+    pub fn tlsa(name: impl Into<String>, tlsa: Tlsa, ttl: u32) -> Self {
+        Self::typed(
+            name,
+            RecordType::Tlsa,
+            &RecordContent::Tlsa(tlsa),
+            ttl,
+            None,
+        )
+    }
+
+    fn typed(
+        name: impl Into<String>,
+        record_type: RecordType,
+        content: &RecordContent,
+        ttl: u32,
+        priority: Option<u32>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            record_type,
+            content: content.to_string(),
+            ttl,
+            priority,
+            redirect_code: None,
+        }
+    }
+
+    /// Builds a `Redirect` record sending `name` to `target`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Invalid` unless `target` is an absolute http(s) URL
+    /// with a host.
+    pub fn redirect(
+        name: impl Into<String>,
+        target: &str,
+        code: RedirectCode,
+        ttl: u32,
+    ) -> Result<Self> {
+        let url = check_redirect_target(target).map_err(Error::Invalid)?;
+        Ok(Self {
+            name: name.into(),
+            record_type: RecordType::Redirect,
+            content: url.into(),
+            ttl,
+            priority: None,
+            redirect_code: Some(code),
+        })
+    }
+}
+
+fn validate_fields(
+    name: &str,
+    record_type: &RecordType,
+    content: &str,
+    ttl: u32,
+    priority: Option<u32>,
+) -> Result<()> {
+    let mut errors = Vec::new();
+    let mut push = |field, message| errors.push(FieldError { field, message });
+
+    if !matches!(name, "" | "@")
+        && let Err(msg) = check_hostname(name)
+    {
+        push("name", msg);
+    }
+    if let Err(msg) = check_content(record_type, content) {
+        push("content", msg);
+    }
+    if !ALLOWED_TTLS.contains(&ttl) {
+        push("ttl", format!("must be one of {ALLOWED_TTLS:?}, got {ttl}"));
+    }
+    if matches!(record_type, RecordType::Mx | RecordType::Srv) && priority.is_none() {
+        push("priority", format!("required for {record_type} records"));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Validation(errors))
+    }
+}
+
+fn check_content(record_type: &RecordType, content: &str) -> std::result::Result<(), String> {
+    let parsed = RecordContent::parse(record_type, content).map_err(|e| match e {
+        Error::Invalid(msg) => msg,
+        other => other.to_string(),
+    })?;
+    match (record_type, parsed) {
+        (_, RecordContent::Srv(srv)) => check_hostname(&srv.target),
+        (
+            RecordType::Aname
+            | RecordType::Cname
+            | RecordType::Mx
+            | RecordType::Ns
+            | RecordType::Ptr,
+            _,
+        ) => check_hostname(content),
+        (RecordType::Redirect, _) => check_redirect_target(content).map(drop),
+        (RecordType::Txt, _) if content.is_empty() => Err("must not be empty".into()),
+        _ => Ok(()),
+    }
+}
+
+/// Checks a (possibly relative) DNS name. A leading `*` label and a
+/// trailing dot are allowed; underscores are too, for `_dmarc` and friends.
+fn check_hostname(host: &str) -> std::result::Result<(), String> {
+    let trimmed = host.strip_suffix('.').unwrap_or(host);
+    if trimmed.is_empty() {
+        return Err("hostname must not be empty".into());
+    }
+    if trimmed.len() > 253 {
+        return Err(format!("hostname longer than 253 characters: {host}"));
+    }
+    for (i, label) in trimmed.split('.').enumerate() {
+        if i == 0 && label == "*" {
+            continue;
+        }
+        if label.is_empty() || label.len() > 63 {
+            return Err(format!("label must be 1-63 characters in {host}"));
+        }
+        if !label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("invalid character in {host}"));
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err(format!("label may not start or end with '-' in {host}"));
+        }
+    }
+    Ok(())
+}
+
+fn check_redirect_target(target: &str) -> std::result::Result<Url, String> {
+    let url = Url::parse(target).map_err(|e| format!("redirect target {target}: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(format!(
+            "redirect target must be an http(s) url, got {target}"
+        ));
+    }
+    Ok(url)
 }
 
 #[derive(Debug, Deserialize)]
-struct RecordsResponse {
-    records: Vec<Record>,
+pub(crate) struct RecordsResponse {
+    pub(crate) records: Vec<Record>,
 }
 
 impl NjallaClient {
@@ -46,6 +386,20 @@ impl NjallaClient {
         Ok(resp.records)
     }
 
+    /// Lists only the `Dynamic` records of a domain, with their update URLs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or API rejection.
+    pub async fn list_dynamic_records(&self, domain: &str) -> Result<Vec<DynamicRecord>> {
+        Ok(self
+            .list_records(domain)
+            .await?
+            .iter()
+            .filter_map(|r| r.dynamic(domain))
+            .collect())
+    }
+
     /// Adds a DNS record to a domain. Returns the created record with its ID.
     ///
     /// # Errors
@@ -78,3 +432,151 @@ impl NjallaClient {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(
+        name: &str,
+        record_type: RecordType,
+        content: &str,
+        priority: Option<u32>,
+    ) -> NewRecord {
+        NewRecord {
+            name: name.into(),
+            record_type,
+            content: content.into(),
+            ttl: 3600,
+            priority,
+            redirect_code: None,
+        }
+    }
+
+    fn invalid_fields(record: &NewRecord) -> Vec<String> {
+        match record.validate() {
+            Ok(()) => Vec::new(),
+            Err(Error::Validation(errors)) => errors.iter().map(|e| e.field.to_owned()).collect(),
+            Err(other) => vec![format!("unexpected error: {other}")],
+        }
+    }
+
+    #[test]
+    fn accepts_wildcard_underscore_and_trailing_dot_names() {
+        for name in [
+            "",
+            "@",
+            "*",
+            "*.sub",
+            "_dmarc",
+            "_sip._tcp",
+            "www.",
+            "a-b.c-d",
+        ] {
+            let rec = record(name, RecordType::Txt, "v=spf1 -all", None);
+            assert!(invalid_fields(&rec).is_empty(), "{name:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_names() {
+        let long_label = "a".repeat(64);
+        for name in [
+            "a..b",
+            "-bad",
+            "bad-",
+            "sp ace",
+            "a.*",
+            "a!",
+            long_label.as_str(),
+        ] {
+            let rec = record(name, RecordType::Txt, "x", None);
+            assert_eq!(invalid_fields(&rec), ["name"], "{name:?}");
+        }
+    }
+
+    #[test]
+    fn checks_hostname_content() {
+        let ok = record("www", RecordType::Cname, "target.example.com.", None);
+        assert!(invalid_fields(&ok).is_empty());
+        let bad = record("www", RecordType::Cname, "not a host", None);
+        assert_eq!(invalid_fields(&bad), ["content"]);
+        let srv = record("_sip._tcp", RecordType::Srv, "5 5060 -bad", Some(10));
+        assert_eq!(invalid_fields(&srv), ["content"]);
+    }
+
+    #[test]
+    fn only_allows_listed_ttls() {
+        for ttl in ALLOWED_TTLS {
+            let rec = NewRecord {
+                ttl,
+                ..record("@", RecordType::A, "1.2.3.4", None)
+            };
+            assert!(invalid_fields(&rec).is_empty(), "{ttl}");
+        }
+        for ttl in [0, 1, 61, 3601, 86_401] {
+            let rec = NewRecord {
+                ttl,
+                ..record("@", RecordType::A, "1.2.3.4", None)
+            };
+            assert_eq!(invalid_fields(&rec), ["ttl"], "{ttl}");
+        }
+    }
+
+    #[test]
+    fn requires_priority_for_mx_and_srv() {
+        let mx = record("@", RecordType::Mx, "mail.example.com", None);
+        assert_eq!(invalid_fields(&mx), ["priority"]);
+        let mx = record("@", RecordType::Mx, "mail.example.com", Some(10));
+        assert!(invalid_fields(&mx).is_empty());
+        let srv = record("_sip._tcp", RecordType::Srv, "5 5060 sip.example.com", None);
+        assert_eq!(invalid_fields(&srv), ["priority"]);
+    }
+
+    #[test]
+    fn reports_every_invalid_field() {
+        let rec = NewRecord {
+            ttl: 1,
+            ..record("-x", RecordType::Mx, "", None)
+        };
+        assert_eq!(invalid_fields(&rec), ["name", "content", "ttl", "priority"]);
+    }
+
+    #[test]
+    fn rejects_empty_txt() {
+        assert_eq!(
+            invalid_fields(&record("@", RecordType::Txt, "", None)),
+            ["content"]
+        );
+    }
+
+    #[test]
+    fn redirect_needs_absolute_http_url() {
+        let ok = NewRecord::redirect("go", "https://example.org/a", RedirectCode::Temporary, 3600);
+        assert_eq!(
+            ok.map(|r| r.content).ok().as_deref(),
+            Some("https://example.org/a")
+        );
+        for target in ["ftp://example.org", "example.org", "https://"] {
+            let rec = NewRecord::redirect("go", target, RedirectCode::Permanent, 3600);
+            assert!(matches!(rec, Err(Error::Invalid(_))), "{target}");
+        }
+    }
+
+    #[test]
+    fn builds_fqdn() {
+        let rec = |name: &str| Record {
+            id: None,
+            name: name.into(),
+            record_type: RecordType::A,
+            content: "1.2.3.4".into(),
+            ttl: 3600,
+            priority: None,
+            key: None,
+            redirect_code: None,
+        };
+        assert_eq!(rec("@").fqdn("example.com"), "example.com");
+        assert_eq!(rec("").fqdn("example.com"), "example.com");
+        assert_eq!(rec("www").fqdn("example.com"), "www.example.com");
+    }
+}
@@ -0,0 +1,382 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// DNS record type.
+///
+/// Parsing with `FromStr` (as the CLI does) rejects unknown types so typos
+/// fail early; spell them `other:<TYPE>` (e.g. `other:HTTPS`) to get
+/// `Other` anyway. Deserializing is lenient: types this crate does not know
+/// yet come back as `Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(into = "String")]
+pub enum RecordType {
+    A,
+    Aaaa,
+    Aname,
+    Caa,
+    Cname,
+    Mx,
+    Naptr,
+    Ns,
+    Ptr,
+    Srv,
+    Sshfp,
+    Tlsa,
+    Txt,
+    Dynamic,
+    Redirect,
+    Other(String),
+}
+
+impl RecordType {
+    const KNOWN: [Self; 15] = [
+        Self::A,
+        Self::Aaaa,
+        Self::Aname,
+        Self::Caa,
+        Self::Cname,
+        Self::Mx,
+        Self::Naptr,
+        Self::Ns,
+        Self::Ptr,
+        Self::Srv,
+        Self::Sshfp,
+        Self::Tlsa,
+        Self::Txt,
+        Self::Dynamic,
+        Self::Redirect,
+    ];
+
+    /// Name as the API spells it, e.g. `AAAA` or `Dynamic`.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::A => "A",
+            Self::Aaaa => "AAAA",
+            Self::Aname => "ANAME",
+            Self::Caa => "CAA",
+            Self::Cname => "CNAME",
+            Self::Mx => "MX",
+            Self::Naptr => "NAPTR",
+            Self::Ns => "NS",
+            Self::Ptr => "PTR",
+            Self::Srv => "SRV",
+            Self::Sshfp => "SSHFP",
+            Self::Tlsa => "TLSA",
+            Self::Txt => "TXT",
+            Self::Dynamic => "Dynamic",
+            Self::Redirect => "Redirect",
+            Self::Other(s) => s,
+        }
+    }
+
+    fn known(s: &str) -> Option<Self> {
+        Self::KNOWN
+            .into_iter()
+            .find(|t| t.as_str().eq_ignore_ascii_case(s))
+    }
+}
+
+impl fmt::Display for RecordType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RecordType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some((prefix, name)) = s.split_once(':')
+            && prefix.eq_ignore_ascii_case("other")
+        {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                return Err(Error::Invalid(format!("invalid record type: {s}")));
+            }
+            return Ok(Self::known(name).unwrap_or_else(|| Self::Other(name.to_owned())));
+        }
+        Self::known(s).ok_or_else(|| {
+            Error::Invalid(format!(
+                "unknown record type: {s} (use other:{s} to send it anyway)"
+            ))
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for RecordType {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(d)?;
+        Ok(Self::known(&s).unwrap_or(Self::Other(s)))
+    }
+}
+
+impl From<RecordType> for String {
+    fn from(t: RecordType) -> Self {
+        match t {
+            RecordType::Other(s) => s,
+            known => known.as_str().to_owned(),
+        }
+    }
+}
+
+/// SRV content: `weight port target`. The priority lives in the record's
+/// `priority` field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Srv {
+    pub weight: u16,
+    pub port: u16,
+    pub target: String,
+}
+
+/// CAA content: `flags tag "value"`, e.g. `0 issue "letsencrypt.org"`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Caa {
+    pub flags: u8,
+    pub tag: String,
+    pub value: String,
+}
+
+/// TLSA content: `usage selector matching_type data`, with `data` in hex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Tlsa {
+    pub usage: u8,
+    pub selector: u8,
+    pub matching_type: u8,
+    pub data: String,
+}
+
+/// Record content parsed according to its type. Types without a structured
+/// form are kept as `Text`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum RecordContent {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Srv(Srv),
+    Caa(Caa),
+    Tlsa(Tlsa),
+    Text(String),
+}
+
+impl RecordContent {
+    /// Parses wire-format `content` for a record of `record_type`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Invalid` if the content does not fit the type.
+    pub fn parse(record_type: &RecordType, content: &str) -> Result<Self> {
+        let invalid =
+            |what: &str| Error::Invalid(format!("{record_type} content {what}: {content}"));
+        let num = |part: Option<&str>, what: &str| -> Result<u16> {
+            part.and_then(|p| p.parse().ok())
+                .ok_or_else(|| invalid(what))
+        };
+        let byte = |part: Option<&str>, what: &str| -> Result<u8> {
+            part.and_then(|p| p.parse().ok())
+                .ok_or_else(|| invalid(what))
+        };
+
+        match record_type {
+            RecordType::A => content
+                .parse()
+                .map(Self::A)
+                .map_err(|_| invalid("must be an ipv4 address")),
+            RecordType::Aaaa => content
+                .parse()
+                .map(Self::Aaaa)
+                .map_err(|_| invalid("must be an ipv6 address")),
+            RecordType::Srv => {
+                let mut parts = content.split_whitespace();
+                let weight = num(parts.next(), "needs a numeric weight")?;
+                let port = num(parts.next(), "needs a numeric port")?;
+                let target = parts.next().ok_or_else(|| invalid("needs a target"))?;
+                if parts.next().is_some() {
+                    return Err(invalid("must be `weight port target`"));
+                }
+                Ok(Self::Srv(Srv {
+                    weight,
+                    port,
+                    target: target.to_owned(),
+                }))
+            }
+            RecordType::Caa => {
+                let mut parts = content.splitn(3, char::is_whitespace);
+                let flags = byte(parts.next(), "needs numeric flags")?;
+                let tag = parts
+                    .next()
+                    .filter(|t| !t.is_empty())
+                    .ok_or_else(|| invalid("needs a tag"))?;
+                let value = parts.next().ok_or_else(|| invalid("needs a value"))?;
+                Ok(Self::Caa(Caa {
+                    flags,
+                    tag: tag.to_owned(),
+                    value: value.trim().trim_matches('"').to_owned(),
+                }))
+            }
+            RecordType::Tlsa => {
+                let mut parts = content.split_whitespace();
+                let usage = byte(parts.next(), "needs a numeric usage")?;
+                let selector = byte(parts.next(), "needs a numeric selector")?;
+                let matching_type = byte(parts.next(), "needs a numeric matching type")?;
+                let data = parts
+                    .next()
+                    .filter(|d| d.chars().all(|c| c.is_ascii_hexdigit()))
+                    .ok_or_else(|| invalid("needs hex data"))?;
+                if parts.next().is_some() {
+                    return Err(invalid("must be `usage selector matching_type data`"));
+                }
+                Ok(Self::Tlsa(Tlsa {
+                    usage,
+                    selector,
+                    matching_type,
+                    data: data.to_owned(),
+                }))
+            }
+            _ => Ok(Self::Text(content.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for RecordContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::A(ip) => write!(f, "{ip}"),
+            Self::Aaaa(ip) => write!(f, "{ip}"),
+            Self::Srv(s) => write!(f, "{} {} {}", s.weight, s.port, s.target),
+            Self::Caa(c) => write!(f, "{} {} \"{}\"", c.flags, c.tag, c.value),
+            Self::Tlsa(t) => write!(
+                f,
+                "{} {} {} {}",
+                t.usage, t.selector, t.matching_type, t.data
+            ),
+            Self::Text(s) => f.write_str(s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(record_type: &RecordType, content: &str) -> Option<String> {
+        RecordContent::parse(record_type, content)
+            .ok()
+            .map(|c| c.to_string())
+    }
+
+    #[test]
+    fn srv_round_trips() {
+        let parsed = RecordContent::parse(&RecordType::Srv, "5 5060 sip.example.com").ok();
+        assert_eq!(
+            parsed,
+            Some(RecordContent::Srv(Srv {
+                weight: 5,
+                port: 5060,
+                target: "sip.example.com".into(),
+            }))
+        );
+        assert_eq!(
+            round_trip(&RecordType::Srv, "5  5060 sip.example.com").as_deref(),
+            Some("5 5060 sip.example.com")
+        );
+    }
+
+    #[test]
+    fn caa_round_trips_quoted_values() {
+        let content = r#"0 issue "letsencrypt.org; validationmethods=dns-01""#;
+        let parsed = RecordContent::parse(&RecordType::Caa, content).ok();
+        assert_eq!(
+            parsed,
+            Some(RecordContent::Caa(Caa {
+                flags: 0,
+                tag: "issue".into(),
+                value: "letsencrypt.org; validationmethods=dns-01".into(),
+            }))
+        );
+        assert_eq!(
+            round_trip(&RecordType::Caa, content).as_deref(),
+            Some(content)
+        );
+        assert_eq!(
+            round_trip(&RecordType::Caa, "128 iodef mailto:a@example.com").as_deref(),
+            Some(r#"128 iodef "mailto:a@example.com""#)
+        );
+    }
+
+    #[test]
+    fn tlsa_round_trips() {
+        let content = "3 1 1 0123456789abcdef";
+        assert_eq!(
+            round_trip(&RecordType::Tlsa, content).as_deref(),
+            Some(content)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_content() {
+        let cases = [
+            (RecordType::A, "1.2.3"),
+            (RecordType::A, "::1"),
+            (RecordType::Aaaa, "1.2.3.4"),
+            (RecordType::Srv, "5 5060"),
+            (RecordType::Srv, "5 5060 a.example.com extra"),
+            (RecordType::Srv, "x 5060 a.example.com"),
+            (RecordType::Caa, "256 issue \"ca\""),
+            (RecordType::Caa, "0"),
+            (RecordType::Tlsa, "3 1 1 xyz"),
+            (RecordType::Tlsa, "3 1 1 ab cd"),
+        ];
+        for (record_type, content) in cases {
+            assert!(
+                matches!(
+                    RecordContent::parse(&record_type, content),
+                    Err(Error::Invalid(_))
+                ),
+                "{record_type} {content:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_other_content_as_text() {
+        assert_eq!(
+            round_trip(&RecordType::Txt, "v=spf1 -all").as_deref(),
+            Some("v=spf1 -all")
+        );
+    }
+
+    #[test]
+    fn parses_known_types_case_insensitively() {
+        assert_eq!("aaaa".parse::<RecordType>().ok(), Some(RecordType::Aaaa));
+        assert_eq!(
+            "dynamic".parse::<RecordType>().ok(),
+            Some(RecordType::Dynamic)
+        );
+        assert!("HTTPS".parse::<RecordType>().is_err());
+    }
+
+    #[test]
+    fn other_prefix_reaches_unknown_types() {
+        assert_eq!(
+            "other:HTTPS".parse::<RecordType>().ok(),
+            Some(RecordType::Other("HTTPS".into()))
+        );
+        assert_eq!("OTHER:mx".parse::<RecordType>().ok(), Some(RecordType::Mx));
+        assert!("other:".parse::<RecordType>().is_err());
+        assert!("other:a b".parse::<RecordType>().is_err());
+    }
+
+    #[test]
+    fn deserializes_unknown_types_leniently() {
+        let parsed: Option<RecordType> = serde_json::from_str(r#""SVCB""#).ok();
+        assert_eq!(parsed, Some(RecordType::Other("SVCB".into())));
+        let parsed: Option<RecordType> = serde_json::from_str(r#""mx""#).ok();
+        assert_eq!(parsed, Some(RecordType::Mx));
+        let serialized = serde_json::to_string(&RecordType::Other("SVCB".into())).ok();
+        assert_eq!(serialized.as_deref(), Some(r#""SVCB""#));
+    }
+}
@@ -0,0 +1,231 @@
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::time::Duration;
+
+use crate::error::Error;
+
+/// How failed API calls are retried.
+///
+/// Only transient failures are retried: connection errors, timeouts, HTTP
+/// 429, 500, 502, 503 and 504. API errors (a JSON-RPC `error`) never are.
+///
+/// By default only read-only methods (`list-*`, `get-*`, `find-domains`,
+/// `check-task`) are retried. Anything that changes state, including calls
+/// that spend money like `register_domain` and `add_server`, is sent once
+/// unless `retry_writes` is enabled, since a request that timed out may
+/// still have gone through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    retry_writes: bool,
+}
+
+impl Default for RetryPolicy {
+    /// 3 retries, backing off from 500ms up to 10s.
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            retry_writes: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retries.
+    #[must_use]
+    pub fn none() -> Self {
+        Self::default().max_retries(0)
+    }
+
+    /// Retries after the first attempt; 0 disables retrying.
+    #[must_use]
+    pub const fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Delay before the first retry, doubled for each one after.
+    #[must_use]
+    pub const fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Upper bound on any single delay. If the server's `Retry-After` asks
+    /// for longer, the call fails instead of retrying early.
+    #[must_use]
+    pub const fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Also retries calls that change state. Those may then run twice, e.g.
+    /// registering a domain or ordering a server, if the first attempt
+    /// reached the API but its response was lost.
+    #[must_use]
+    pub const fn retry_writes(mut self, retry_writes: bool) -> Self {
+        self.retry_writes = retry_writes;
+        self
+    }
+
+    /// How many times a call to `method` may be retried.
+    pub(crate) fn retries_for(&self, method: &str) -> u32 {
+        if self.may_resend(method) {
+            self.max_retries
+        } else {
+            0
+        }
+    }
+
+    /// Whether a call to `method` may be sent again after it possibly ran.
+    pub(crate) fn may_resend(&self, method: &str) -> bool {
+        self.retry_writes || is_read_only(method)
+    }
+
+    /// Delay before retry number `retry` (starting at 1): exponential
+    /// backoff with jitter, or `retry_after` if the server asked for longer.
+    /// `None` if `retry_after` exceeds `max_delay`.
+    pub(crate) fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_delay);
+        let jittered = backoff.mul_f64(fastrand::f64().mul_add(0.5, 0.5));
+        match retry_after {
+            Some(after) if after > self.max_delay => None,
+            Some(after) => Some(after.max(jittered)),
+            None => Some(jittered),
+        }
+    }
+}
+
+fn is_read_only(method: &str) -> bool {
+    method.starts_with("list-")
+        || method.starts_with("get-")
+        || matches!(method, "find-domains" | "check-task")
+}
+
+/// Whether an HTTP status is worth retrying.
+fn is_transient_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Whether an error is worth retrying.
+pub(crate) fn is_transient(err: &Error) -> bool {
+    match err {
+        Error::Http(e) => {
+            e.is_connect() || e.is_timeout() || e.status().is_some_and(is_transient_status)
+        }
+        _ => false,
+    }
+}
+
+/// `Retry-After` in seconds. HTTP dates are ignored.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let secs = headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn backoff_doubles_within_jitter_bounds() {
+        let policy = RetryPolicy::default()
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_secs(1));
+        for (retry, full) in [
+            (1, 100),
+            (2, 200),
+            (3, 400),
+            (4, 800),
+            (5, 1000),
+            (30, 1000),
+        ] {
+            let full = Duration::from_millis(full);
+            for _ in 0..100 {
+                let delay = policy.delay(retry, None);
+                assert!(
+                    delay.is_some_and(|d| d >= full / 2 && d <= full),
+                    "retry {retry}: {delay:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn retry_after_extends_but_never_exceeds_max_delay() {
+        let policy = RetryPolicy::default()
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_secs(10));
+        let after = Duration::from_secs(5);
+        assert_eq!(policy.delay(1, Some(after)), Some(after));
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(10))),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(policy.delay(1, Some(Duration::from_mins(1))), None);
+        let short = policy.delay(3, Some(Duration::ZERO));
+        assert!(short.is_some_and(|d| d >= Duration::from_millis(200)));
+    }
+
+    #[test]
+    fn only_retries_reads_unless_opted_in() {
+        let policy = RetryPolicy::default().max_retries(4);
+        for method in ["list-domains", "get-domain", "find-domains", "check-task"] {
+            assert_eq!(policy.retries_for(method), 4, "{method}");
+        }
+        for method in ["register-domain", "add-server", "edit-record", "renew-vpn"] {
+            assert_eq!(policy.retries_for(method), 0, "{method}");
+            assert!(!policy.may_resend(method), "{method}");
+        }
+        let policy = policy.retry_writes(true);
+        assert_eq!(policy.retries_for("register-domain"), 4);
+        assert_eq!(RetryPolicy::none().retries_for("list-domains"), 0);
+    }
+
+    #[test]
+    fn parses_retry_after_seconds_only() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, HeaderValue::from_static(" 7 "));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn only_transient_errors_are_retried() {
+        assert!(!is_transient(&Error::MissingResult));
+        assert!(!is_transient(&Error::Api {
+            code: 500,
+            message: "internal".into(),
+        }));
+        assert!(is_transient_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_transient_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_transient_status(StatusCode::BAD_REQUEST));
+        assert!(!is_transient_status(StatusCode::NOT_IMPLEMENTED));
+    }
+}
@@ -1,8 +1,17 @@
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::net::IpAddr;
+use std::time::Duration;
 
 use crate::client::NjallaClient;
-use crate::error::Result;
+use crate::domain::TaskResponse;
+use crate::error::{Error, Result};
+use crate::expiry::Expiry;
+use crate::record_type::RecordType;
+use crate::status::{OsState, ServerStatus};
+use crate::task::TaskHandle;
+
+const SERVER_POLL_INTERVAL: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Server {
@@ -10,14 +19,14 @@ pub struct Server {
     #[serde(rename = "type")]
     pub server_type: String,
     pub id: String,
-    pub status: String,
+    pub status: ServerStatus,
     pub os: String,
-    pub expiry: String,
+    pub expiry: Expiry,
     pub autorenew: bool,
     pub ssh_key: String,
     pub ips: Vec<String>,
     pub reverse_name: String,
-    pub os_state: String,
+    pub os_state: OsState,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -30,6 +39,41 @@ pub struct NewServer {
     pub months: u32,
 }
 
+/// Patch for `edit_server`. Only fields that are `Some` are sent.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ServerUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autorenew: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssh_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reverse_name: Option<String>,
+}
+
+/// Forward-confirmed reverse DNS result for one server IP.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReverseDnsCheck {
+    pub ip: IpAddr,
+    pub reverse_name: String,
+    /// A/AAAA contents found for `reverse_name` in the account's zones.
+    pub forward: Vec<String>,
+    /// Whether `forward` contains `ip`.
+    pub confirmed: bool,
+}
+
+impl ReverseDnsCheck {
+    fn new(ip: IpAddr, reverse_name: &str, forward: Vec<String>) -> Self {
+        Self {
+            ip,
+            reverse_name: reverse_name.to_owned(),
+            confirmed: forward.iter().any(|f| f.parse::<IpAddr>() == Ok(ip)),
+            forward,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct ServersResponse {
     servers: Vec<Server>,
@@ -113,6 +157,192 @@ impl NjallaClient {
         self.call("restart-server", json!({ "id": id })).await
     }
 
+    /// Edits a server without touching its data. Only the fields set in
+    /// `update` are changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the server is not found.
+    pub async fn edit_server(&self, id: &str, update: &ServerUpdate) -> Result<Server> {
+        let mut params = serde_json::to_value(update)?;
+        params["id"] = json!(id);
+        self.call("edit-server", params).await
+    }
+
+    /// Renews a server for a given number of months. Returns a handle to the task.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if renewal is rejected.
+    pub async fn renew_server(&self, id: &str, months: u32) -> Result<TaskHandle> {
+        let resp: TaskResponse = self
+            .call("renew-server", json!({ "id": id, "months": months }))
+            .await?;
+        Ok(self.task(resp.task))
+    }
+
+    /// Sets the reverse DNS (PTR) name for one of a server's IPs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the server is not found.
+    pub async fn set_reverse_name(&self, id: &str, ip: IpAddr, name: &str) -> Result<Server> {
+        self.call(
+            "edit-server",
+            json!({ "id": id, "ip": ip, "reverse_name": name }),
+        )
+        .await
+    }
+
+    /// Checks forward-confirmed reverse DNS for every IP of a server: the
+    /// PTR name must resolve back to the same IP through an A/AAAA record
+    /// in one of the account's domains.
+    ///
+    /// The API reports one `reverse_name` per server, so it is checked for
+    /// every IP. After setting a different name for a single IP, check that
+    /// pair with `check_reverse_name` instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotFound` if the server does not exist, or an error on
+    /// network failure or API rejection.
+    pub async fn check_reverse_dns(&self, id: &str) -> Result<Vec<ReverseDnsCheck>> {
+        let server = self
+            .list_servers()
+            .await?
+            .into_iter()
+            .find(|s| s.id == id)
+            .ok_or_else(|| Error::NotFound(format!("server {id}")))?;
+        let forward = self.forward_addresses(&server.reverse_name).await?;
+
+        Ok(server
+            .ips
+            .iter()
+            .filter_map(|ip| ip.parse::<IpAddr>().ok())
+            .map(|ip| ReverseDnsCheck::new(ip, &server.reverse_name, forward.clone()))
+            .collect())
+    }
+
+    /// Checks forward-confirmed reverse DNS for one IP and PTR name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or API rejection.
+    pub async fn check_reverse_name(&self, ip: IpAddr, name: &str) -> Result<ReverseDnsCheck> {
+        let forward = self.forward_addresses(name).await?;
+        Ok(ReverseDnsCheck::new(ip, name, forward))
+    }
+
+    /// A/AAAA contents for `name` in the account's domain that holds it.
+    async fn forward_addresses(&self, name: &str) -> Result<Vec<String>> {
+        let name = name.trim_end_matches('.').to_ascii_lowercase();
+        let zone = self
+            .list_domains()
+            .await?
+            .into_iter()
+            .map(|d| d.name.to_ascii_lowercase())
+            .filter(|d| name == *d || name.ends_with(&format!(".{d}")))
+            .max_by_key(String::len);
+
+        let mut forward = Vec::new();
+        if let Some(zone) = zone {
+            for rec in self.list_records(&zone).await? {
+                if !matches!(rec.record_type, RecordType::A | RecordType::Aaaa) {
+                    continue;
+                }
+                if rec.fqdn(&zone).eq_ignore_ascii_case(&name) {
+                    forward.push(rec.content);
+                }
+            }
+        }
+        Ok(forward)
+    }
+
+    /// Polls `list_servers` until the server reaches `desired`. When waiting
+    /// for `Running`, also waits until the OS is no longer installing and
+    /// the server has at least one IP. Returns the server in its final state.
+    ///
+    /// Right after `restart_server` or `reset_server` the server still looks
+    /// running; use `wait_for_server_restart` there.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if the state is not reached within `timeout`,
+    /// `Error::NotFound` if the server disappears, or an error on network
+    /// failure.
+    pub async fn wait_for_server(
+        &self,
+        id: &str,
+        desired: ServerStatus,
+        timeout: Duration,
+    ) -> Result<Server> {
+        let deadline = tokio::time::Instant::now().checked_add(timeout);
+        let what = format!("wanted {desired}");
+        self.poll_server(id, deadline, timeout, &what, |server| {
+            server.status == desired && (desired != ServerStatus::Running || is_up(server))
+        })
+        .await
+    }
+
+    /// Polls `list_servers` after `restart_server` or `reset_server`: first
+    /// until the server goes down or starts reinstalling, then until it is
+    /// running again with the OS installed and an IP.
+    ///
+    /// # Errors
+    ///
+    /// Same as `wait_for_server`. A restart so quick that no poll sees the
+    /// server down ends in `Error::Timeout`.
+    pub async fn wait_for_server_restart(&self, id: &str, timeout: Duration) -> Result<Server> {
+        let deadline = tokio::time::Instant::now().checked_add(timeout);
+        self.poll_server(
+            id,
+            deadline,
+            timeout,
+            "waiting for it to go down",
+            |server| {
+                server.status != ServerStatus::Running || server.os_state == OsState::Installing
+            },
+        )
+        .await?;
+        self.poll_server(id, deadline, timeout, "wanted running", is_up)
+            .await
+    }
+
+    /// Polls until `done` holds. A `deadline` of `None`, for a `timeout` too
+    /// long to represent, means no deadline.
+    async fn poll_server(
+        &self,
+        id: &str,
+        deadline: Option<tokio::time::Instant>,
+        timeout: Duration,
+        what: &str,
+        done: impl Fn(&Server) -> bool,
+    ) -> Result<Server> {
+        loop {
+            let server = self
+                .list_servers()
+                .await?
+                .into_iter()
+                .find(|s| s.id == id)
+                .ok_or_else(|| Error::NotFound(format!("server {id}")))?;
+            if done(&server) {
+                return Ok(server);
+            }
+            let mut wait = SERVER_POLL_INTERVAL;
+            if let Some(deadline) = deadline {
+                let now = tokio::time::Instant::now();
+                if now >= deadline {
+                    return Err(Error::Timeout(format!(
+                        "server {id} still {} after {timeout:?}, {what}",
+                        server.status
+                    )));
+                }
+                wait = wait.min(deadline - now);
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+
     /// Factory resets a server with new settings. **Destroys all data.**
     ///
     /// # Errors
@@ -141,3 +371,10 @@ impl NjallaClient {
         self.call("remove-server", json!({ "id": id })).await
     }
 }
+
+/// Whether a server is running, done installing and reachable.
+fn is_up(server: &Server) -> bool {
+    server.status == ServerStatus::Running
+        && server.os_state != OsState::Installing
+        && !server.ips.is_empty()
+}
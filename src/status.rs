@@ -0,0 +1,150 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Defines a status enum that maps known API strings (case-insensitively,
+/// with aliases) to variants and keeps anything else in `Unknown`, so new
+/// states on the API side never break deserialization.
+macro_rules! status_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($variant:ident => $wire:literal $(| $alias:literal)*,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)*
+            Unknown(String),
+        }
+
+        impl $name {
+            /// Name as the API spells it.
+            #[must_use]
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $wire,)*
+                    Self::Unknown(s) => s,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(s: &str) -> Self {
+                match s.to_ascii_lowercase().as_str() {
+                    $($wire $(| $alias)* => Self::$variant,)*
+                    _ => Self::Unknown(s.to_owned()),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                s.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                Ok(String::deserialize(d)?.as_str().into())
+            }
+        }
+    };
+}
+
+status_enum! {
+    /// Registration state of a domain.
+    DomainStatus {
+        Active => "active",
+        Pending => "pending" | "registering",
+        Inactive => "inactive",
+        Expired => "expired",
+    }
+}
+
+status_enum! {
+    /// Power state of a server.
+    ServerStatus {
+        Running => "running",
+        Stopped => "stopped",
+        Starting => "starting",
+        Stopping => "stopping",
+        Pending => "pending" | "creating",
+    }
+}
+
+status_enum! {
+    /// Installation state of a server's operating system.
+    OsState {
+        Installed => "installed" | "ready",
+        Installing => "installing" | "reinstalling",
+    }
+}
+
+status_enum! {
+    /// State of an async task such as a domain registration.
+    TaskStatus {
+        Pending => "pending" | "queued",
+        Running => "running" | "processing",
+        Done => "done" | "completed" | "success",
+        Failed => "failed" | "error",
+    }
+}
+
+impl TaskStatus {
+    /// Whether the task may still change state.
+    #[must_use]
+    pub const fn is_pending(&self) -> bool {
+        matches!(self, Self::Pending | Self::Running)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_case_insensitively() {
+        assert_eq!(ServerStatus::from("RUNNING"), ServerStatus::Running);
+        assert_eq!(DomainStatus::from("Active"), DomainStatus::Active);
+        assert_eq!(TaskStatus::from("dOnE"), TaskStatus::Done);
+    }
+
+    #[test]
+    fn aliases_map_to_their_variant() {
+        assert_eq!(DomainStatus::from("registering"), DomainStatus::Pending);
+        assert_eq!(ServerStatus::from("creating"), ServerStatus::Pending);
+        assert_eq!(OsState::from("ready"), OsState::Installed);
+        assert_eq!(OsState::from("reinstalling"), OsState::Installing);
+        for (wire, status) in [
+            ("queued", TaskStatus::Pending),
+            ("processing", TaskStatus::Running),
+            ("completed", TaskStatus::Done),
+            ("success", TaskStatus::Done),
+            ("error", TaskStatus::Failed),
+        ] {
+            assert_eq!(TaskStatus::from(wire), status, "{wire}");
+        }
+        // Known variants serialize under their canonical name.
+        assert_eq!(TaskStatus::from("Completed").as_str(), "done");
+    }
+
+    #[test]
+    fn unknown_keeps_its_original_spelling() {
+        let parsed: Option<ServerStatus> = serde_json::from_str(r#""Hibernating""#).ok();
+        let status = ServerStatus::Unknown("Hibernating".into());
+        assert_eq!(parsed.as_ref(), Some(&status));
+        assert_eq!(status.to_string(), "Hibernating");
+        assert_eq!(
+            serde_json::to_string(&status).ok().as_deref(),
+            Some(r#""Hibernating""#)
+        );
+        assert!(!TaskStatus::from("paused").is_pending());
+    }
+}
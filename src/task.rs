@@ -0,0 +1,100 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::client::NjallaClient;
+use crate::error::{Error, Result};
+use crate::status::TaskStatus;
+
+/// An async task started by a call such as `register_domain`.
+#[derive(Debug, Clone)]
+pub struct TaskHandle {
+    client: NjallaClient,
+    id: String,
+}
+
+impl TaskHandle {
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Checks the current status once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or API rejection.
+    pub async fn status(&self) -> Result<TaskStatus> {
+        self.client.check_task(&self.id).await
+    }
+
+    /// Polls every `interval` until the task is `Done` and returns that
+    /// status. States this crate does not know count as still pending, so
+    /// an unexpected status never passes for success.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::TaskFailed` if the task fails, `Error::Timeout` if it
+    /// is still pending after `timeout`, or an error on network failure.
+    pub async fn wait(&self, timeout: Duration, interval: Duration) -> Result<TaskStatus> {
+        self.wait_with_progress(timeout, interval, |_| {}).await
+    }
+
+    /// Like `wait`, calling `on_status` with every status seen while pending.
+    ///
+    /// # Errors
+    ///
+    /// Same as `wait`.
+    pub async fn wait_with_progress(
+        &self,
+        timeout: Duration,
+        interval: Duration,
+        mut on_status: impl FnMut(&TaskStatus),
+    ) -> Result<TaskStatus> {
+        // No deadline if `timeout` is too long to represent, e.g. `Duration::MAX`.
+        let deadline = tokio::time::Instant::now().checked_add(timeout);
+        loop {
+            let status = self.status().await?;
+            match status {
+                TaskStatus::Done => return Ok(status),
+                TaskStatus::Failed => {
+                    return Err(Error::TaskFailed {
+                        id: self.id.clone(),
+                        status,
+                    });
+                }
+                TaskStatus::Pending | TaskStatus::Running | TaskStatus::Unknown(_) => {
+                    on_status(&status);
+                }
+            }
+            let mut wait = interval;
+            if let Some(deadline) = deadline {
+                let now = tokio::time::Instant::now();
+                if now >= deadline {
+                    return Err(Error::Timeout(format!(
+                        "task {} still {status} after {timeout:?}",
+                        self.id
+                    )));
+                }
+                wait = wait.min(deadline - now);
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+impl fmt::Display for TaskHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.id)
+    }
+}
+
+impl NjallaClient {
+    /// Returns a handle for an existing task ID.
+    #[must_use]
+    pub fn task(&self, id: impl Into<String>) -> TaskHandle {
+        TaskHandle {
+            client: self.clone(),
+            id: id.into(),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::net::IpAddr;
+
+use crate::client::NjallaClient;
+use crate::error::Result;
+
+/// An API token. Empty `from`/`acl` lists mean no restriction.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Token {
+    pub key: String,
+    #[serde(default)]
+    pub comment: String,
+    #[serde(default)]
+    pub from: Vec<IpAddr>,
+    #[serde(default)]
+    pub acl: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NewToken {
+    pub comment: String,
+    pub from: Vec<IpAddr>,
+    pub acl: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokensResponse {
+    tokens: Vec<Token>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyResponse {
+    key: String,
+}
+
+impl NjallaClient {
+    /// Lists all API tokens on the account.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or API rejection.
+    pub async fn list_tokens(&self) -> Result<Vec<Token>> {
+        let resp: TokensResponse = self.call("list-tokens", json!({})).await?;
+        Ok(resp.tokens)
+    }
+
+    /// Creates a new API token. Returns the new key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or invalid parameters.
+    pub async fn add_token(&self, token: &NewToken) -> Result<String> {
+        let params = serde_json::to_value(token)?;
+        let resp: KeyResponse = self.call("add-token", params).await?;
+        Ok(resp.key)
+    }
+
+    /// Edits an existing API token. All fields are sent; fetch first if patching.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the token is not found.
+    pub async fn edit_token(&self, token: &Token) -> Result<()> {
+        let params = serde_json::to_value(token)?;
+        self.call_void("edit-token", params).await
+    }
+
+    /// Removes an API token by key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the token is not found.
+    pub async fn remove_token(&self, key: &str) -> Result<()> {
+        self.call_void("remove-token", json!({ "key": key })).await
+    }
+}
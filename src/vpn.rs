@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::str::FromStr;
+
+use crate::client::NjallaClient;
+use crate::domain::TaskResponse;
+use crate::error::{Error, Result};
+use crate::expiry::Expiry;
+use crate::task::TaskHandle;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Vpn {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    pub expiry: Expiry,
+    pub autorenew: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publickey: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NewVpn {
+    pub name: String,
+    pub autorenew: bool,
+    pub months: u32,
+}
+
+/// Patch for `edit_vpn`. Only fields that are `Some` are sent.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VpnUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autorenew: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publickey: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VpnProtocol {
+    Wireguard,
+    Openvpn,
+}
+
+impl FromStr for VpnProtocol {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "wireguard" | "wg" => Ok(Self::Wireguard),
+            "openvpn" | "ovpn" => Ok(Self::Openvpn),
+            other => Err(Error::Invalid(format!("unknown vpn protocol: {other}"))),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct VpnsResponse {
+    vpns: Vec<Vpn>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VpnConfigResponse {
+    config: String,
+}
+
+impl NjallaClient {
+    /// Lists all VPNs on the account.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or API rejection.
+    pub async fn list_vpns(&self) -> Result<Vec<Vpn>> {
+        let resp: VpnsResponse = self.call("list-vpns", json!({})).await?;
+        Ok(resp.vpns)
+    }
+
+    /// Gets a single VPN by ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the VPN is not found.
+    pub async fn get_vpn(&self, id: &str) -> Result<Vpn> {
+        self.call("get-vpn", json!({ "id": id })).await
+    }
+
+    /// Creates a new VPN. Returns the created VPN.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or invalid parameters.
+    pub async fn add_vpn(&self, vpn: &NewVpn) -> Result<Vpn> {
+        let params = serde_json::to_value(vpn)?;
+        self.call("add-vpn", params).await
+    }
+
+    /// Edits a VPN. Only the fields set in `update` are changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the VPN is not found.
+    pub async fn edit_vpn(&self, id: &str, update: &VpnUpdate) -> Result<Vpn> {
+        let mut params = serde_json::to_value(update)?;
+        params["id"] = json!(id);
+        self.call("edit-vpn", params).await
+    }
+
+    /// Renews a VPN for a given number of months. Returns a handle to the task.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if renewal is rejected.
+    pub async fn renew_vpn(&self, id: &str, months: u32) -> Result<TaskHandle> {
+        let resp: TaskResponse = self
+            .call("renew-vpn", json!({ "id": id, "months": months }))
+            .await?;
+        Ok(self.task(resp.task))
+    }
+
+    /// Removes a VPN.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the VPN is not found.
+    pub async fn remove_vpn(&self, id: &str) -> Result<()> {
+        self.call_void("remove-vpn", json!({ "id": id })).await
+    }
+
+    /// Gets a ready-to-use client configuration file for a VPN.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the VPN is not found.
+    pub async fn get_vpn_config(&self, id: &str, protocol: VpnProtocol) -> Result<String> {
+        let resp: VpnConfigResponse = self
+            .call(
+                "get-vpn-client-config",
+                json!({ "id": id, "type": protocol }),
+            )
+            .await?;
+        Ok(resp.config)
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::client::NjallaClient;
+use crate::error::{Error, Result};
+
+/// A wallet amount in whole euros. Negative for spending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Amount(pub i64);
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "€{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaymentMethod {
+    Paypal,
+    Bitcoin,
+    Litecoin,
+    Monero,
+    Zcash,
+    Ethereum,
+}
+
+impl FromStr for PaymentMethod {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "paypal" => Ok(Self::Paypal),
+            "bitcoin" | "btc" => Ok(Self::Bitcoin),
+            "litecoin" | "ltc" => Ok(Self::Litecoin),
+            "monero" | "xmr" => Ok(Self::Monero),
+            "zcash" | "zec" => Ok(Self::Zcash),
+            "ethereum" | "eth" => Ok(Self::Ethereum),
+            other => Err(Error::Invalid(format!("unknown payment method: {other}"))),
+        }
+    }
+}
+
+/// A wallet refill. `address` is set for crypto payments, `url` for paypal.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Payment {
+    pub id: String,
+    pub amount: Amount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Transaction {
+    pub id: String,
+    pub amount: Amount,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pdf: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BalanceResponse {
+    balance: Amount,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransactionsResponse {
+    transactions: Vec<Transaction>,
+}
+
+impl NjallaClient {
+    /// Gets the current wallet balance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or API rejection.
+    pub async fn get_balance(&self) -> Result<Amount> {
+        let resp: BalanceResponse = self.call("get-balance", json!({})).await?;
+        Ok(resp.balance)
+    }
+
+    /// Starts a wallet refill. Returns where and how much to pay.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the amount is rejected.
+    pub async fn add_payment(&self, amount: Amount, via: PaymentMethod) -> Result<Payment> {
+        self.call("add-payment", json!({ "amount": amount, "via": via }))
+            .await
+    }
+
+    /// Gets the details and status of a payment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or if the payment is not found.
+    pub async fn get_payment(&self, id: &str) -> Result<Payment> {
+        self.call("get-payment", json!({ "id": id })).await
+    }
+
+    /// Lists all wallet transactions on the account.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on network failure or API rejection.
+    pub async fn list_transactions(&self) -> Result<Vec<Transaction>> {
+        let resp: TransactionsResponse = self.call("list-transactions", json!({})).await?;
+        Ok(resp.transactions)
+    }
+}